@@ -1,6 +1,6 @@
 use std::env;
 fn main() {
-    if let Ok(_) = env::var("CARGO_CFG_WINDOWS") {
+    if env::var("CARGO_CFG_WINDOWS").is_ok() {
         let _ = embed_resource::compile("resources.rc", embed_resource::NONE);
     }
 
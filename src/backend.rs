@@ -0,0 +1,216 @@
+#![deny(clippy::all)]
+
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::Deserialize;
+use steamworks::{ItemState, SteamError};
+
+use crate::bounded::run_bounded;
+use crate::collections::{CollectionError, CollectionItem, CollectionSource};
+use crate::presets::WorkshopDetails;
+
+/// Max items per workshop query, steam pages anything above it
+pub const QUERY_PAGE_SIZE: usize = 50;
+
+/// Future returned by the backend, boxed so the trait can be used as `dyn WorkshopBackend`
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+#[derive(Debug, Clone)]
+pub enum WorkshopError {
+    /// The query could not be created
+    Query(String),
+    /// Steam answered, but with an error
+    Steam(SteamError),
+    /// Steam did not answer in time
+    Timeout(Duration),
+    /// The callback was dropped without an answer
+    Disconnected,
+    /// The backend has no steam client to ask
+    Offline,
+    /// A mod folder could not be changed
+    Io(String),
+}
+
+impl fmt::Display for WorkshopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkshopError::Query(e) => write!(f, "Failed creating workshop query: {}", e),
+            WorkshopError::Steam(e) => write!(f, "Steam returned an error: {}", e),
+            WorkshopError::Timeout(timeout) => {
+                write!(f, "Steam did not answer within {}s", timeout.as_secs())
+            }
+            WorkshopError::Disconnected => {
+                write!(f, "Steam callback was dropped without an answer")
+            }
+            WorkshopError::Offline => write!(f, "Not connected to steam"),
+            WorkshopError::Io(e) => write!(f, "Failed changing mod folder: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for WorkshopError {}
+
+impl From<SteamError> for WorkshopError {
+    fn from(e: SteamError) -> Self {
+        WorkshopError::Steam(e)
+    }
+}
+
+/// Whether the backend is hearing from steam
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SteamStatus {
+    Connected,
+    /// Steam callbacks have not run for the given time
+    NotResponding(Duration),
+    /// Steam answers, but has lost its connection to the steam servers or is shutting down
+    Disconnected,
+    /// The backend does not talk to steam at all
+    #[default]
+    Offline,
+}
+
+/// Workshop item metadata as returned by a query
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WorkshopItem {
+    pub id: u64,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub is_collection: bool,
+    /// Items a collection contains, or the dependencies of a mod
+    #[serde(default)]
+    pub children: Vec<u64>,
+    #[serde(flatten)]
+    pub details: WorkshopDetails,
+}
+
+/// Where and how big an item is on disk
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct InstallInfo {
+    pub folder: PathBuf,
+    pub size_on_disk: u64,
+    #[serde(default)]
+    pub timestamp: u32,
+}
+
+/// Everything AMDU needs from steam. Implemented by `Workshop`, by `FakeWorkshop`, which
+/// runs from a recorded fixture instead of a steam client, and by `OfflineWorkshop`, which reads
+/// the mod folders on disk
+pub trait WorkshopBackend: Send + Sync {
+    /// Ids of all subscribed items
+    fn subscribed_items(&self) -> Vec<u64>;
+
+    /// Metadata of at most `QUERY_PAGE_SIZE` items, items the workshop does not know are left out.
+    /// Use `query_items_paged` for more
+    fn query_items(&self, ids: Vec<u64>)
+        -> BoxFuture<'_, Result<Vec<WorkshopItem>, WorkshopError>>;
+
+    fn install_info(&self, id: u64) -> Option<InstallInfo>;
+
+    /// What steam is doing with the item, backends without steam only know whether it is installed
+    fn item_state(&self, id: u64) -> ItemState {
+        match self.install_info(id) {
+            Some(_) => ItemState::SUBSCRIBED | ItemState::INSTALLED,
+            None => ItemState::NONE,
+        }
+    }
+
+    /// Bytes downloaded and total bytes of an item steam is downloading
+    fn download_progress(&self, _id: u64) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// Asks steam to download or update the item right away
+    fn download(&self, _id: u64) -> Result<(), WorkshopError> {
+        Err(WorkshopError::Offline)
+    }
+
+    fn subscribe(&self, id: u64) -> BoxFuture<'_, Result<(), WorkshopError>>;
+
+    fn unsubscribe(&self, id: u64) -> BoxFuture<'_, Result<(), WorkshopError>>;
+
+    /// Whether backends that remove mod folders themselves skip the trash, steam cleans up its own
+    fn set_delete_permanently(&self, _permanently: bool) {}
+
+    fn is_dlc_installed(&self, dlc_app_id: u32) -> bool;
+
+    /// Install folder of the game itself, where local @mods usually live
+    fn game_install_dir(&self) -> Option<PathBuf>;
+
+    /// Persona name of a workshop author, steam looks up unknown ones and has them on a later call
+    fn author_name(&self, _owner: u64) -> Option<String> {
+        None
+    }
+
+    /// Steam libraries on this machine, their manifests answer what the steam api can not
+    fn steam_libraries(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+
+    fn steam_status(&self) -> SteamStatus {
+        SteamStatus::Offline
+    }
+
+    /// Stops anything the backend runs in the background
+    fn shutdown(&self) {}
+}
+
+/// Number of pages `query_items_paged` needs for the given amount of items
+pub fn page_count(items: usize) -> usize {
+    items.div_ceil(QUERY_PAGE_SIZE)
+}
+
+/// Queries any number of items a page at a time, running up to `concurrency` pages at once.
+/// `progress` is bumped for every finished page, and the items keep the order of `ids`
+pub async fn query_items_paged(
+    backend: &Arc<dyn WorkshopBackend>,
+    ids: Vec<u64>,
+    concurrency: usize,
+    progress: &AtomicU32,
+) -> Result<Vec<WorkshopItem>, WorkshopError> {
+    let pages: Vec<Vec<u64>> = ids.chunks(QUERY_PAGE_SIZE).map(<[u64]>::to_vec).collect();
+    let mut results: Vec<Vec<WorkshopItem>> = vec![vec![]; pages.len()];
+    let queries = pages.into_iter().map(|page| {
+        let backend = backend.clone();
+        async move { backend.query_items(page).await }
+    });
+
+    run_bounded::<_, WorkshopError>(queries, concurrency, |index, joined| {
+        results[index] = joined.map_err(|_| WorkshopError::Disconnected)??;
+        progress.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    })
+    .await?;
+    Ok(results.into_iter().flatten().collect())
+}
+
+impl CollectionSource for dyn WorkshopBackend + '_ {
+    async fn query_collection_items(
+        &self,
+        ids: Vec<u64>,
+    ) -> Result<Vec<CollectionItem>, CollectionError> {
+        let items = self
+            .query_items(ids)
+            .await
+            .map_err(|e| CollectionError::Query(e.to_string()))?;
+        Ok(items
+            .into_iter()
+            .map(|item| CollectionItem {
+                id: item.id,
+                title: item.title,
+                is_collection: item.is_collection,
+                children: item.children,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,97 @@
+#![deny(clippy::all)]
+
+use std::collections::HashMap;
+use std::future::Future;
+
+use tokio::task::{JoinError, JoinSet};
+
+/// Runs the tasks, at most `concurrency` at the same time. `on_done` gets the index and output of
+/// every task in the order they finish, and an error from it stops the tasks still left
+pub async fn run_bounded<F, E>(
+    tasks: impl IntoIterator<Item = F>,
+    concurrency: usize,
+    mut on_done: impl FnMut(usize, Result<F::Output, JoinError>) -> Result<(), E>,
+) -> Result<(), E>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let mut pending = tasks.into_iter().enumerate();
+    let mut running = JoinSet::new();
+    let mut indexes = HashMap::new();
+
+    loop {
+        // keep the queue topped up, then wait for whichever task is done first
+        while running.len() < concurrency.max(1) {
+            let Some((index, task)) = pending.next() else {
+                break;
+            };
+            indexes.insert(running.spawn(task).id(), index);
+        }
+
+        match running.join_next_with_id().await {
+            Some(Ok((id, output))) => on_done(indexes[&id], Ok(output))?,
+            Some(Err(e)) => on_done(indexes[&e.id()], Err(e))?,
+            None => break,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_at_most_concurrency_tasks() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let most = Arc::new(AtomicUsize::new(0));
+        let tasks = (0..10u64).map(|i| {
+            let running = running.clone();
+            let most = most.clone();
+            async move {
+                most.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                // later tasks finish first, so the order of finishing differs from the input
+                tokio::time::sleep(Duration::from_millis(10 - i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i * 10
+            }
+        });
+
+        let mut outputs = vec![0; 10];
+        run_bounded::<_, ()>(tasks, 3, |index, output| {
+            outputs[index] = output.unwrap();
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(most.load(Ordering::SeqCst), 3);
+        assert_eq!(outputs, (0..10).map(|i| i * 10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn error_stops_the_rest() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let tasks = (0..10).map(|i| {
+            let started = started.clone();
+            async move {
+                started.fetch_add(1, Ordering::SeqCst);
+                i
+            }
+        });
+
+        let result = run_bounded(tasks, 1, |_, output| match output.unwrap() {
+            2 => Err("third task"),
+            _ => Ok(()),
+        })
+        .await;
+
+        assert_eq!(result, Err("third task"));
+        assert_eq!(started.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,221 @@
+#![deny(clippy::all)]
+
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+
+use crate::backend::QUERY_PAGE_SIZE;
+use crate::presets::{Mod, ModPreset};
+
+/// Workshop item as seen while walking a collection
+#[derive(Debug, Clone)]
+pub struct CollectionItem {
+    pub id: u64,
+    pub title: String,
+    pub is_collection: bool,
+    pub children: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum CollectionError {
+    Query(String),
+    NotFound(u64),
+    NotACollection(u64),
+}
+
+impl fmt::Display for CollectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CollectionError::Query(e) => write!(f, "Workshop query failed: {}", e),
+            CollectionError::NotFound(id) => write!(f, "Workshop item {} not found", id),
+            CollectionError::NotACollection(id) => {
+                write!(f, "Workshop item {} is not a collection", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CollectionError {}
+
+/// Looks up workshop items including their children. Implemented for every `WorkshopBackend`,
+/// and by anything else standing in for steam
+pub trait CollectionSource {
+    fn query_collection_items(
+        &self,
+        ids: Vec<u64>,
+    ) -> impl Future<Output = Result<Vec<CollectionItem>, CollectionError>> + Send;
+}
+
+/// Resolves a collection into a preset of all mods in it, walking nested collections as well
+pub async fn resolve_collection<S: CollectionSource + ?Sized>(
+    source: &S,
+    collection_id: u64,
+) -> Result<ModPreset, CollectionError> {
+    let root = query_paged(source, vec![collection_id])
+        .await?
+        .into_iter()
+        .find(|item| item.id == collection_id)
+        .ok_or(CollectionError::NotFound(collection_id))?;
+    if !root.is_collection {
+        return Err(CollectionError::NotACollection(collection_id));
+    }
+
+    // breadth first through the children, remembering what we have seen so collections linking each other don't loop
+    let mut seen: HashSet<u64> = HashSet::from([collection_id]);
+    let mut queue: Vec<u64> = root.children.clone();
+    let mut mods = vec![];
+
+    while !queue.is_empty() {
+        queue.retain(|id| seen.insert(*id));
+        if queue.is_empty() {
+            break;
+        }
+
+        let mut next = vec![];
+        for item in query_paged(source, std::mem::take(&mut queue)).await? {
+            if item.is_collection {
+                next.extend(item.children);
+            } else {
+                mods.push(Mod::from_workshop_id(item.id, Some(item.title)));
+            }
+        }
+        queue = next;
+    }
+
+    Ok(ModPreset::from_mods(root.title, mods))
+}
+
+async fn query_paged<S: CollectionSource + ?Sized>(
+    source: &S,
+    ids: Vec<u64>,
+) -> Result<Vec<CollectionItem>, CollectionError> {
+    let mut items = vec![];
+    for page in ids.chunks(QUERY_PAGE_SIZE) {
+        items.append(&mut source.query_collection_items(page.to_vec()).await?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Workshop in memory, remembering how many ids every query asked for
+    #[derive(Default)]
+    struct MemorySource {
+        items: HashMap<u64, CollectionItem>,
+        queries: Mutex<Vec<usize>>,
+    }
+
+    impl MemorySource {
+        fn collection(mut self, id: u64, title: &str, children: Vec<u64>) -> Self {
+            self.items.insert(
+                id,
+                CollectionItem {
+                    id,
+                    title: title.to_string(),
+                    is_collection: true,
+                    children,
+                },
+            );
+            self
+        }
+
+        fn mods(mut self, ids: impl IntoIterator<Item = u64>) -> Self {
+            for id in ids {
+                self.items.insert(
+                    id,
+                    CollectionItem {
+                        id,
+                        title: format!("mod {}", id),
+                        is_collection: false,
+                        children: vec![],
+                    },
+                );
+            }
+            self
+        }
+    }
+
+    impl CollectionSource for MemorySource {
+        async fn query_collection_items(
+            &self,
+            ids: Vec<u64>,
+        ) -> Result<Vec<CollectionItem>, CollectionError> {
+            self.queries.lock().unwrap().push(ids.len());
+            // like steam, unknown ids are left out of the answer
+            Ok(ids
+                .iter()
+                .filter_map(|id| self.items.get(id).cloned())
+                .collect())
+        }
+    }
+
+    fn mod_ids(preset: &ModPreset) -> Vec<u64> {
+        let mut ids = preset.get_id_list();
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn nested_collections_are_walked() {
+        let source = MemorySource::default()
+            .collection(1, "Ops", vec![10, 2])
+            .collection(2, "Ops Maps", vec![20, 3])
+            .collection(3, "Ops Maps Extra", vec![30])
+            .mods([10, 20, 30]);
+
+        let preset = resolve_collection(&source, 1).await.unwrap();
+
+        assert_eq!(preset.name, "Ops");
+        assert_eq!(mod_ids(&preset), vec![10, 20, 30]);
+        assert_eq!(preset.mods[0].name, "mod 10");
+    }
+
+    #[tokio::test]
+    async fn collections_linking_each_other_resolve_once() {
+        let source = MemorySource::default()
+            .collection(1, "Ops", vec![10, 2])
+            .collection(2, "Ops Maps", vec![20, 1, 10])
+            .mods([10, 20]);
+
+        let preset = resolve_collection(&source, 1).await.unwrap();
+
+        assert_eq!(mod_ids(&preset), vec![10, 20]);
+    }
+
+    #[tokio::test]
+    async fn big_collections_are_queried_in_pages() {
+        let children: Vec<u64> = (100..100 + QUERY_PAGE_SIZE as u64 + 1).collect();
+        let source = MemorySource::default()
+            .collection(1, "Everything", children.clone())
+            .mods(children.clone());
+
+        let preset = resolve_collection(&source, 1).await.unwrap();
+
+        assert_eq!(mod_ids(&preset), children);
+        // the root, then a full page and the one item left over
+        assert_eq!(*source.queries.lock().unwrap(), vec![1, QUERY_PAGE_SIZE, 1]);
+    }
+
+    #[tokio::test]
+    async fn single_mod_is_not_a_collection() {
+        let source = MemorySource::default().mods([10]);
+
+        let result = resolve_collection(&source, 10).await;
+
+        assert!(matches!(result, Err(CollectionError::NotACollection(10))));
+    }
+
+    #[tokio::test]
+    async fn unknown_id_is_not_found() {
+        let source = MemorySource::default().collection(1, "Ops", vec![]);
+
+        let result = resolve_collection(&source, 2).await;
+
+        assert!(matches!(result, Err(CollectionError::NotFound(2))));
+    }
+}
@@ -0,0 +1,252 @@
+#![deny(clippy::all)]
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use steamworks::PublishedFileId;
+
+use crate::modset::ModSet;
+use crate::presets::{Mod, ModPreset};
+
+/// Mod together with the names of the presets that list it
+#[derive(Debug, Clone)]
+pub struct PresetMod {
+    pub item: Mod,
+    pub presets: Vec<String>,
+    /// Names of kept mods that need this mod, directly or through other dependencies
+    pub required_by: Vec<String>,
+}
+
+/// Outcome of comparing the subscribed mods against the presets to keep
+#[derive(Debug, Clone, Default)]
+pub struct ModDiff {
+    /// Subscribed, but in none of the presets and not needed by a kept mod
+    pub remove: Vec<Mod>,
+    /// Subscribed and in at least one preset or needed by a kept mod, with the presets keeping it
+    pub kept: Vec<PresetMod>,
+    /// In a preset or needed by a kept mod, but not subscribed
+    pub not_subscribed: Vec<PresetMod>,
+    /// Presets that leave out dependencies of their own mods
+    pub incomplete: Vec<String>,
+}
+
+/// Compares the subscribed mods against the presets to keep. `workshop` holds the workshop data of
+/// preset mods we are not subscribed to, their dependencies are needed just as much
+pub fn diff_mods(keep_sets: &[ModPreset], subscribed: &[Mod], workshop: &[Mod]) -> ModDiff {
+    let subscribed_set: ModSet = subscribed.iter().cloned().collect();
+
+    // if we have no presets, everything can be removed
+    if keep_sets.is_empty() {
+        return ModDiff {
+            remove: sorted_by_name(subscribed_set.into_vec()),
+            ..Default::default()
+        };
+    }
+
+    // membership is by workshop id, so preset names that differ from the workshop title don't matter
+    let keep_set: ModSet = keep_sets
+        .iter()
+        .flat_map(|preset| preset.mods.clone())
+        .collect();
+    let presets_keeping = |id: PublishedFileId| -> Vec<String> {
+        keep_sets
+            .iter()
+            .filter(|preset| preset.mods.iter().any(|item| item.id == id.0))
+            .map(|preset| preset.name.clone())
+            .collect()
+    };
+    // everything we know the dependencies of, the subscribed data wins over the queried data
+    let known_set = subscribed_set.union(&workshop.iter().cloned().collect());
+    // workshop data has the real name, presets might only know the id
+    let name_of = |id: u64| -> String {
+        known_set
+            .get(PublishedFileId(id))
+            .or(keep_set.get(PublishedFileId(id)))
+            .map(|item| item.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    };
+
+    // walk the dependencies of every kept mod, subscribed or not
+    let mut required_by: BTreeMap<u64, BTreeSet<String>> = BTreeMap::new();
+    let mut incomplete = vec![];
+    for preset in keep_sets {
+        let listed: HashSet<u64> = preset.mods.iter().map(|item| item.id).collect();
+        let mut missing: BTreeMap<u64, BTreeSet<String>> = BTreeMap::new();
+
+        for item in &preset.mods {
+            for dependency in dependencies_of(item.id, &known_set) {
+                required_by
+                    .entry(dependency)
+                    .or_default()
+                    .insert(name_of(item.id));
+                if !listed.contains(&dependency) {
+                    missing
+                        .entry(dependency)
+                        .or_default()
+                        .insert(name_of(item.id));
+                }
+            }
+        }
+
+        incomplete.extend(missing.into_iter().map(|(dependency, needed_by)| {
+            format!(
+                "{} is incomplete, {} is required by {}",
+                preset.name,
+                name_of(dependency),
+                needed_by.into_iter().collect::<Vec<_>>().join(", ")
+            )
+        }));
+    }
+
+    // dependencies are kept like the mods listed in the presets
+    let mut protected_set = keep_set.clone();
+    protected_set.extend(required_by.keys().map(|id| {
+        known_set
+            .get(PublishedFileId(*id))
+            .cloned()
+            .unwrap_or_else(|| Mod::from_workshop_id(*id, None))
+    }));
+    let preset_mod = |item: Mod| -> PresetMod {
+        PresetMod {
+            presets: presets_keeping(PublishedFileId(item.id)),
+            required_by: required_by
+                .get(&item.id)
+                .map(|names| names.iter().cloned().collect())
+                .unwrap_or_default(),
+            item,
+        }
+    };
+
+    let remove = sorted_by_name(subscribed_set.difference(&protected_set).into_vec());
+
+    // kept mods carry the subscribed data, like install size, not the preset entry
+    let mut kept: Vec<PresetMod> = subscribed_set
+        .intersection(&protected_set)
+        .into_iter()
+        .map(preset_mod)
+        .collect();
+    kept.sort_by_key(|kept| kept.item.name.to_lowercase());
+
+    let mut not_subscribed: Vec<PresetMod> = protected_set
+        .difference(&subscribed_set)
+        .into_iter()
+        .map(preset_mod)
+        .collect();
+    not_subscribed.sort_by_key(|missing| missing.item.name.to_lowercase());
+
+    ModDiff {
+        remove,
+        kept,
+        not_subscribed,
+        incomplete,
+    }
+}
+
+/// Everything the mod depends on, including dependencies of dependencies
+fn dependencies_of(id: u64, known: &ModSet) -> Vec<u64> {
+    let mut seen: HashSet<u64> = HashSet::from([id]);
+    let mut queue = vec![id];
+    let mut dependencies = vec![];
+
+    while let Some(next) = queue.pop() {
+        let Some(item) = known.get(PublishedFileId(next)) else {
+            continue;
+        };
+        for dependency in &item.dependencies {
+            if seen.insert(*dependency) {
+                dependencies.push(*dependency);
+                queue.push(*dependency);
+            }
+        }
+    }
+    dependencies
+}
+
+fn sorted_by_name(mut mods: Vec<Mod>) -> Vec<Mod> {
+    mods.sort_by_key(|item| item.name.to_lowercase());
+    mods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_name_differing_from_workshop_title_is_kept() {
+        let preset = ModPreset::from_mods(
+            "Ops".to_string(),
+            vec![Mod::from_workshop_id(
+                450814997,
+                Some("CBA (renamed in launcher)".to_string()),
+            )],
+        );
+        let subscribed = vec![
+            Mod::from_workshop_id(450814997, Some("CBA_A3".to_string())),
+            Mod::from_workshop_id(463939057, Some("ace".to_string())),
+        ];
+
+        let diff = diff_mods(&[preset], &subscribed, &[]);
+
+        assert_eq!(diff.kept.len(), 1);
+        // the kept mod carries the workshop data, not the name from the preset
+        assert_eq!(diff.kept[0].item.name, "CBA_A3");
+        assert_eq!(diff.kept[0].presets, vec!["Ops".to_string()]);
+        assert_eq!(
+            diff.remove.iter().map(|item| item.id).collect::<Vec<_>>(),
+            vec![463939057]
+        );
+        assert!(diff.not_subscribed.is_empty());
+    }
+
+    #[test]
+    fn dependency_of_unsubscribed_preset_mod_is_kept() {
+        // the preset lists ace but leaves out cba, and this member only has cba so far
+        let preset = ModPreset::from_mods(
+            "Ops".to_string(),
+            vec![Mod::from_workshop_id(463939057, None)],
+        );
+        let subscribed = vec![
+            Mod::from_workshop_id(450814997, Some("CBA_A3".to_string())),
+            Mod::from_workshop_id(751965892, Some("ACRE2".to_string())),
+        ];
+        let mut ace = Mod::from_workshop_id(463939057, Some("ace".to_string()));
+        ace.dependencies = vec![450814997];
+
+        let diff = diff_mods(&[preset], &subscribed, &[ace]);
+
+        assert_eq!(
+            diff.remove.iter().map(|item| item.id).collect::<Vec<_>>(),
+            vec![751965892]
+        );
+        assert_eq!(diff.kept.len(), 1);
+        assert_eq!(diff.kept[0].item.id, 450814997);
+        assert_eq!(diff.kept[0].required_by, vec!["ace".to_string()]);
+        assert_eq!(diff.not_subscribed.len(), 1);
+        assert_eq!(diff.not_subscribed[0].item.id, 463939057);
+        assert_eq!(
+            diff.incomplete,
+            vec!["Ops is incomplete, CBA_A3 is required by ace".to_string()]
+        );
+    }
+
+    #[test]
+    fn missing_dependency_of_unsubscribed_preset_mod_is_subscribed() {
+        let preset = ModPreset::from_mods(
+            "Ops".to_string(),
+            vec![Mod::from_workshop_id(463939057, None)],
+        );
+        let mut ace = Mod::from_workshop_id(463939057, Some("ace".to_string()));
+        ace.dependencies = vec![450814997];
+        let cba = Mod::from_workshop_id(450814997, Some("CBA_A3".to_string()));
+
+        let diff = diff_mods(&[preset], &[], &[ace, cba]);
+
+        let missing: Vec<u64> = diff
+            .not_subscribed
+            .iter()
+            .map(|missing| missing.item.id)
+            .collect();
+        assert_eq!(missing, vec![463939057, 450814997]);
+        assert!(diff.not_subscribed[0].required_by.is_empty());
+        assert_eq!(diff.not_subscribed[1].required_by, vec!["ace".to_string()]);
+    }
+}
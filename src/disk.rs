@@ -0,0 +1,59 @@
+#![deny(clippy::all)]
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::bounded::run_bounded;
+
+/// Differences below this are noise, steam and the file system count a little differently
+const MISMATCH_MIN_BYTES: u64 = 1_000_000;
+
+/// Total size of all files below `path`, symlinks are not followed
+pub fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut size = 0;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                size += entry.metadata()?.len();
+            }
+        }
+    }
+    Ok(size)
+}
+
+/// Measures the folders of the given items, `concurrency` at a time. Folders that can not be read
+/// are left out
+pub async fn measure_dirs(dirs: Vec<(u64, PathBuf)>, concurrency: usize) -> HashMap<u64, u64> {
+    let tasks = dirs.into_iter().map(|(id, dir)| async move {
+        tokio::task::spawn_blocking(move || (id, dir_size(&dir), dir)).await
+    });
+    let mut sizes = HashMap::new();
+
+    let _ = run_bounded::<_, Infallible>(tasks, concurrency, |_, joined| {
+        match joined.and_then(|measured| measured) {
+            Ok((id, Ok(size), _)) => {
+                sizes.insert(id, size);
+            }
+            Ok((_, Err(e), dir)) => println!("Failed measuring {:?}: {}", dir, e),
+            Err(e) => println!("Failed measuring folder: {}", e),
+        }
+        Ok(())
+    })
+    .await;
+    sizes
+}
+
+/// Whether the size steam reports is far enough off the measured size to point at a broken install
+pub fn sizes_mismatch(reported: u64, measured: u64) -> bool {
+    let difference = reported.abs_diff(measured);
+    difference > MISMATCH_MIN_BYTES && difference > reported.max(measured) / 10
+}
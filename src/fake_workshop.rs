@@ -0,0 +1,169 @@
+#![deny(clippy::all)]
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use steamworks::{ItemState, SteamError};
+
+use crate::backend::{BoxFuture, InstallInfo, WorkshopBackend, WorkshopError, WorkshopItem};
+use crate::presets::Mod;
+
+/// Recorded workshop state, as stored in a fixture file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Fixture {
+    #[serde(default)]
+    pub game_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub installed_dlcs: Vec<u32>,
+    /// Persona names of the item owners
+    #[serde(default)]
+    pub authors: HashMap<u64, String>,
+    pub items: Vec<FixtureItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FixtureItem {
+    #[serde(flatten)]
+    pub item: WorkshopItem,
+    #[serde(default)]
+    pub subscribed: bool,
+    #[serde(default)]
+    pub installed: Option<InstallInfo>,
+    /// Installed, but an update is waiting on the workshop
+    #[serde(default)]
+    pub needs_update: bool,
+}
+
+/// In-memory workshop backed by a fixture, subscribing and unsubscribing only change the fixture state
+pub struct FakeWorkshop {
+    items: HashMap<u64, WorkshopItem>,
+    installed: HashMap<u64, InstallInfo>,
+    subscribed: Mutex<BTreeSet<u64>>,
+    needs_update: HashSet<u64>,
+    installed_dlcs: Vec<u32>,
+    authors: HashMap<u64, String>,
+    game_dir: Option<PathBuf>,
+}
+
+impl FakeWorkshop {
+    pub fn new(fixture: Fixture) -> Self {
+        let mut items = HashMap::new();
+        let mut installed = HashMap::new();
+        let mut subscribed = BTreeSet::new();
+        let mut needs_update = HashSet::new();
+
+        for mut entry in fixture.items {
+            let id = entry.item.id;
+            if entry.item.url.is_empty() {
+                entry.item.url = Mod::from_workshop_id(id, None).url;
+            }
+            if entry.subscribed {
+                subscribed.insert(id);
+            }
+            if entry.needs_update {
+                needs_update.insert(id);
+            }
+            if let Some(info) = entry.installed {
+                installed.insert(id, info);
+            }
+            items.insert(id, entry.item);
+        }
+
+        FakeWorkshop {
+            items,
+            installed,
+            subscribed: Mutex::new(subscribed),
+            needs_update,
+            installed_dlcs: fixture.installed_dlcs,
+            authors: fixture.authors,
+            game_dir: fixture.game_dir,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let fixture: Fixture =
+            serde_json::from_str(json).map_err(|e| format!("Invalid fixture: {}", e))?;
+        Ok(FakeWorkshop::new(fixture))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed reading fixture {:?}: {}", path, e))?;
+        FakeWorkshop::from_json(&json)
+    }
+}
+
+impl WorkshopBackend for FakeWorkshop {
+    fn subscribed_items(&self) -> Vec<u64> {
+        self.subscribed.lock().unwrap().iter().copied().collect()
+    }
+
+    fn query_items(
+        &self,
+        ids: Vec<u64>,
+    ) -> BoxFuture<'_, Result<Vec<WorkshopItem>, WorkshopError>> {
+        let items = ids
+            .iter()
+            .filter_map(|id| self.items.get(id).cloned())
+            .collect();
+        Box::pin(async move { Ok(items) })
+    }
+
+    fn install_info(&self, id: u64) -> Option<InstallInfo> {
+        // like steam, only subscribed items are installed
+        match self.subscribed.lock().unwrap().contains(&id) {
+            true => self.installed.get(&id).cloned(),
+            false => None,
+        }
+    }
+
+    fn item_state(&self, id: u64) -> ItemState {
+        let mut state = ItemState::NONE;
+        if self.subscribed.lock().unwrap().contains(&id) {
+            state |= ItemState::SUBSCRIBED;
+        }
+        if self.install_info(id).is_some() {
+            state |= ItemState::INSTALLED;
+            if self.needs_update.contains(&id) {
+                state |= ItemState::NEEDS_UPDATE;
+            }
+        }
+        state
+    }
+
+    fn subscribe(&self, id: u64) -> BoxFuture<'_, Result<(), WorkshopError>> {
+        let result = match self.items.contains_key(&id) {
+            true => {
+                self.subscribed.lock().unwrap().insert(id);
+                Ok(())
+            }
+            false => Err(WorkshopError::Steam(SteamError::FileNotFound)),
+        };
+        Box::pin(async move { result })
+    }
+
+    fn unsubscribe(&self, id: u64) -> BoxFuture<'_, Result<(), WorkshopError>> {
+        let result = match self.items.contains_key(&id) {
+            true => {
+                self.subscribed.lock().unwrap().remove(&id);
+                Ok(())
+            }
+            false => Err(WorkshopError::Steam(SteamError::FileNotFound)),
+        };
+        Box::pin(async move { result })
+    }
+
+    fn is_dlc_installed(&self, dlc_app_id: u32) -> bool {
+        self.installed_dlcs.contains(&dlc_app_id)
+    }
+
+    fn game_install_dir(&self) -> Option<PathBuf> {
+        self.game_dir.clone()
+    }
+
+    fn author_name(&self, owner: u64) -> Option<String> {
+        self.authors.get(&owner).cloned()
+    }
+}
@@ -0,0 +1,64 @@
+#![deny(clippy::all)]
+
+use std::path::{Path, PathBuf};
+
+use crate::steam_library::{self, ARMA_APP_ID};
+
+/// Candidate folders where the Arma 3 Launcher keeps its presets. Only folders that exist are returned.
+pub fn find_preset_dirs(game_install_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut candidates = vec![];
+
+    // windows keeps it in local appdata
+    if let Some(local_app_data) = std::env::var_os("LOCALAPPDATA") {
+        candidates.push(
+            PathBuf::from(local_app_data)
+                .join("Arma 3 Launcher")
+                .join("Presets"),
+        );
+    }
+
+    // on linux the launcher runs through proton, so the folder is inside the wine prefix of the library arma is installed in
+    if cfg!(target_os = "linux") {
+        candidates.extend(
+            steam_library::find_libraries(game_install_dir)
+                .iter()
+                .map(|library| proton_preset_dir(&library.join("steamapps"))),
+        );
+    }
+
+    // the libraries are already unique, so only the folders that exist are left to pick
+    candidates.retain(|dir| dir.is_dir());
+    candidates
+}
+
+fn proton_preset_dir(steamapps: &Path) -> PathBuf {
+    steamapps
+        .join("compatdata")
+        .join(ARMA_APP_ID.to_string())
+        .join("pfx/drive_c/users/steamuser/AppData/Local/Arma 3 Launcher/Presets")
+}
+
+/// All preset files in the given launcher folders
+pub async fn list_preset_files(dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut files = vec![];
+    for dir in dirs {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                println!("Could not read launcher preset folder {:?}: {}", dir, e);
+                continue;
+            }
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let is_preset = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("preset2"));
+            if is_preset {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
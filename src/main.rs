@@ -1,42 +1,112 @@
 #![deny(clippy::all)]
 #![windows_subsystem = "windows"]
 
-use std::collections::BTreeSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{PathBuf};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use crate::widgets::modrow::{Message as RowMessage, ModRow};
+use crate::widgets::modrow::{Column, Message as RowMessage, ModRow};
 use humansize::{format_size, DECIMAL};
 use iced::alignment::{Horizontal, Vertical};
 use iced::event::Event;
-use iced::widget::{button, column, container, horizontal_rule, horizontal_space, progress_bar, row, scrollable, text, vertical_rule, vertical_space, Space};
+use iced::widget::{
+    button, checkbox, column, container, horizontal_rule, horizontal_space, pick_list,
+    progress_bar, row, scrollable, text, text_input, vertical_rule, vertical_space, Space,
+};
 use iced::{event, time, window, Element, Length, Subscription, Task, Theme};
 use iced::window::{icon};
-use steamworks::{AppId, PublishedFileId};
-use tokio::sync::oneshot;
+use steamworks::{AppId, ItemState};
 
-use crate::presets::{Mod, ModPreset, PresetParser};
-use crate::workshop::Workshop;
+use crate::backend::{query_items_paged, SteamStatus, WorkshopBackend, WorkshopError};
+use crate::collections::CollectionError;
+use crate::diff::ModDiff;
+use crate::fake_workshop::FakeWorkshop;
+use crate::offline_workshop::OfflineWorkshop;
+use crate::orphans::OrphanFolder;
+use crate::presets::{Mod, ModPreset, PresetError, PresetFileResult, PresetParser, PresetSource};
+use crate::sync::SyncPlan;
+use crate::workshop::{Workshop, DEFAULT_CALLBACK_INTERVAL, DEFAULT_TIMEOUT};
+use crate::workshop_ref::parse_workshop_ref;
 
+pub mod backend;
+pub mod bounded;
+pub mod collections;
+pub mod diff;
+pub mod disk;
+pub mod fake_workshop;
+pub mod launcher;
+pub mod modmeta;
+pub mod modset;
+pub mod offline_workshop;
+pub mod orphans;
 pub mod presets;
+pub mod steam_library;
+pub mod sync;
+pub mod vdf;
 pub mod widgets;
 pub mod workshop;
+pub mod workshop_ref;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Workshop pages queried at the same time when loading metadata
+const QUERY_CONCURRENCY: usize = 4;
+/// Mod folders measured at the same time when steam does not know their size, or when verifying
+const MEASURE_CONCURRENCY: usize = 4;
+/// Wait before the first automatic reconnect, doubled on every failed attempt up to the max
+const RETRY_BACKOFF_START: Duration = Duration::from_secs(2);
+const RETRY_BACKOFF_MAX: Duration = Duration::from_secs(60);
 
 struct Amdu {
     // parser: Arc<Mutex<PresetParser>>,
-    workshop: Option<Arc<Workshop>>,
+    workshop: Option<Arc<dyn WorkshopBackend>>,
     error: String,
+    launch: Result<LaunchMode, String>,
+    auto_retry: bool,
+    retry_attempt: u32,
+    retry_generation: u32,
+    retry_delay: Option<Duration>,
     parser: PresetParser,
     mod_selection_list: Vec<ModRow>,
+    kept_list: Vec<ModRow>,
+    not_subscribed_list: Vec<ModRow>,
+    orphan_folders: Vec<OrphanFolder>,
+    orphan_list: Vec<ModRow>,
+    delete_permanently: bool,
+    list_view: ListView,
+    state_filter: StateFilter,
+    /// Steam state and download progress of the subscribed mods
+    item_states: HashMap<u64, (ItemState, Option<(u64, u64)>)>,
+    /// Persona names of mod authors steam already told us
+    author_names: HashMap<u64, String>,
     workshop_subbed_mods: Vec<Mod>,
+    /// Preset mods we are not subscribed to and their dependencies, as the workshop knows them
+    workshop_preset_mods: Vec<Mod>,
+    /// Ids already asked the workshop for, so unknown items are not asked again every change
+    preset_queries: HashSet<u64>,
+    failed_presets: Vec<(PathBuf, PresetError)>,
+    missing_content: Vec<String>,
+    incomplete_presets: Vec<String>,
+    launcher_preset_dirs: Vec<PathBuf>,
+    collection_input: String,
+    collection_error: Option<String>,
     toggle_all_state: bool,
-    unsub_in_progress: bool,
-    unsub_total_count: u32,
-    unsub_progress: Arc<AtomicU32>,
+    sync_plan: Option<Arc<SyncPlan>>,
+    batch_in_progress: Option<BatchAction>,
+    /// Why the last batch or sync plan failed, shown above the list until the next one
+    action_error: Option<String>,
+    batch_total_count: u32,
+    batch_progress: Arc<AtomicU32>,
+    loading_page_count: Option<u32>,
+    loading_progress: Arc<AtomicU32>,
+    /// Why the subscribed mods could not be fetched, shown instead of the list
+    fetch_error: Option<String>,
+    steam_status: SteamStatus,
+    verifying: bool,
+    /// Mods whose folder size is far off the size steam reports, as (reported, measured)
+    size_mismatches: HashMap<u64, (u64, u64)>,
+    mod_columns: Vec<Column>,
 }
 
 #[derive(Debug, Clone)]
@@ -44,28 +114,204 @@ enum Message {
     EventOccurred(Event),
     OpenFileDialog,
     FilesPicked(Result<Arc<Vec<PathBuf>>, Error>),
-    FilesParsed(Result<Arc<Vec<ModPreset>>, String>),
+    FilesParsed(Arc<Vec<PresetFileResult>>),
+    LauncherPresetsFound(Arc<Vec<PathBuf>>),
+    LauncherPresetsParsed(Arc<Vec<PresetFileResult>>),
+    TogglePreset(usize, bool),
+    CollectionInputChanged(String),
+    AddCollection,
+    CollectionResolved(u64, Result<ModPreset, CollectionError>),
+    ExportMergedPresets,
+    ExportSubscribedMods,
+    PresetExported(Result<PathBuf, Error>),
     List(usize, RowMessage),
-    SubscribedModsFetched(Result<Arc<Vec<Mod>>, oneshot::error::RecvError>),
+    SubscribedModsFetched(Result<Arc<Vec<Mod>>, WorkshopError>),
+    ReloadSubscribed,
     LocalFileSizeFetched(Result<Arc<Vec<Mod>>, String>),
+    OrphansFound(Arc<Vec<OrphanFolder>>),
     Init(Result<(), String>),
     ToggleAll,
+    VerifySizes,
+    SizesVerified(Arc<HashMap<u64, (u64, u64)>>),
     UnsubSelected,
-    UnsubProgress(Instant),
-    UnsubbedSelectedMods(Result<(), String>),
-    UpdateSelectionView(Arc<Vec<Mod>>),
+    SubscribeSelected,
+    DeleteSelectedFolders,
+    ToggleDeletePermanently(bool),
+    PlanSync,
+    SyncPlanned(Result<Arc<SyncPlan>, WorkshopError>),
+    ConfirmSync,
+    CancelSync,
+    BatchProgress,
+    CheckSteam,
+    Retry,
+    AutoRetry(u32),
+    ToggleAutoRetry(bool),
+    BatchFinished(Result<(), String>),
+    UpdateSelectionView(Arc<ModDiff>),
+    /// The asked ids, so a failed query can be asked again
+    PresetModsFetched(Vec<u64>, Result<Arc<Vec<Mod>>, WorkshopError>),
+    ShowList(ListView),
+    FilterState(StateFilter),
+    DownloadSelected,
+    ToggleColumn(Column, bool),
+}
+
+/// Where the workshop data comes from, picked by the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LaunchMode {
+    /// Steam client, waiting at most `timeout` on each call
+    Steam {
+        timeout: Duration,
+        callback_interval: Duration,
+    },
+    /// Recorded subscriptions from a fixture file, no steam client needed
+    Demo(PathBuf),
+    /// Mod folders on disk, for servers without a steam client
+    Offline(PathBuf),
+}
+
+impl LaunchMode {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<LaunchMode, String> {
+        let mut demo = None;
+        let mut offline = None;
+        let mut timeout = DEFAULT_TIMEOUT;
+        let mut callback_interval = DEFAULT_CALLBACK_INTERVAL;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--demo" => match args.next() {
+                    Some(path) => demo = Some(PathBuf::from(path)),
+                    None => {
+                        return Err("--demo needs a fixture file: --demo <fixture.json>".to_string())
+                    }
+                },
+                "--offline" => match args.next() {
+                    Some(path) => offline = Some(PathBuf::from(path)),
+                    None => {
+                        return Err("--offline needs a mod folder: --offline <folder>".to_string())
+                    }
+                },
+                "--timeout" => match args.next().and_then(|secs| secs.parse().ok()) {
+                    Some(secs) => timeout = Duration::from_secs(secs),
+                    None => {
+                        return Err(
+                            "--timeout needs the seconds to wait on steam: --timeout <seconds>"
+                                .to_string(),
+                        )
+                    }
+                },
+                "--callback-interval" => match args.next().and_then(|ms| ms.parse().ok()) {
+                    Some(ms) => callback_interval = Duration::from_millis(ms),
+                    None => {
+                        return Err(
+                            "--callback-interval needs the milliseconds between steam callbacks: --callback-interval <ms>"
+                                .to_string(),
+                        )
+                    }
+                },
+                _ => return Err(format!("Unknown argument '{}'", arg)),
+            }
+        }
+        match (demo, offline) {
+            (Some(_), Some(_)) => Err("--demo and --offline can not be used together".to_string()),
+            (Some(path), None) => Ok(LaunchMode::Demo(path)),
+            (None, Some(path)) => Ok(LaunchMode::Offline(path)),
+            (None, None) => Ok(LaunchMode::Steam {
+                timeout,
+                callback_interval,
+            }),
+        }
+    }
+
+    /// Connects to the workshop data this mode points at
+    fn connect(&self) -> Result<Arc<dyn WorkshopBackend>, String> {
+        match self {
+            LaunchMode::Steam {
+                timeout,
+                callback_interval,
+            } => Workshop::new(AppId(steam_library::ARMA_APP_ID)).map(|ws| {
+                Arc::new(
+                    ws.with_timeout(*timeout)
+                        .with_callback_interval(*callback_interval),
+                ) as Arc<dyn WorkshopBackend>
+            }),
+            LaunchMode::Demo(path) => {
+                FakeWorkshop::load(path).map(|ws| Arc::new(ws) as Arc<dyn WorkshopBackend>)
+            }
+            LaunchMode::Offline(path) => {
+                OfflineWorkshop::scan(path).map(|ws| Arc::new(ws) as Arc<dyn WorkshopBackend>)
+            }
+        }
+    }
+}
+
+/// Workshop action running over the selected mods
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BatchAction {
+    Unsubscribe,
+    Subscribe,
+    Sync,
+    DeleteFolders,
+}
+
+/// Which result of the diff the mod list shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListView {
+    Remove,
+    Kept,
+    NotSubscribed,
+    /// Workshop folders left on disk for items we are not subscribed to
+    Orphaned,
+}
+
+/// Which mods of the shown list are visible, by their steam state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StateFilter {
+    All,
+    NeedsUpdate,
+    NotInstalled,
+}
+
+impl StateFilter {
+    const ALL: [StateFilter; 3] = [
+        StateFilter::All,
+        StateFilter::NeedsUpdate,
+        StateFilter::NotInstalled,
+    ];
+
+    /// Rows without a steam state, like mods we are not subscribed to, are only shown for `All`
+    fn matches(&self, row: &ModRow) -> bool {
+        match self {
+            StateFilter::All => true,
+            StateFilter::NeedsUpdate => row
+                .state
+                .is_some_and(|state| state.contains(ItemState::NEEDS_UPDATE)),
+            StateFilter::NotInstalled => row
+                .state
+                .is_some_and(|state| !state.contains(ItemState::INSTALLED)),
+        }
+    }
+}
+
+impl std::fmt::Display for StateFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateFilter::All => write!(f, "All mods"),
+            StateFilter::NeedsUpdate => write!(f, "Needs update"),
+            StateFilter::NotInstalled => write!(f, "Not installed"),
+        }
+    }
 }
 
 impl Amdu {
 
-    fn new() -> (Self, Task<Message>) {
+    fn new(launch: Result<LaunchMode, String>) -> (Self, Task<Message>) {
         // let mut parser = Arc::new(Mutex::new(PresetParser::new()));
 
-        let mut ws: Option<Arc<Workshop>> = None;
+        let mut ws: Option<Arc<dyn WorkshopBackend>> = None;
         let mut err: String = "".to_string();
 
-        match Workshop::new(AppId(107410)) {
-            Ok(result) => ws = Option::from(Arc::new(result)),
+        match launch.clone().and_then(|mode| mode.connect()) {
+            Ok(result) => ws = Some(result),
             Err(e) => err = e,
         }
 
@@ -74,29 +320,72 @@ impl Amdu {
             println!("Workshop Error: {:?}", err);
         }
 
+        let mut amdu = Self {
+            workshop: ws,
+            error: err,
+            launch,
+            auto_retry: true,
+            retry_attempt: 0,
+            retry_generation: 0,
+            retry_delay: None,
+            parser: PresetParser::new(),
+            mod_selection_list: vec![],
+            kept_list: vec![],
+            not_subscribed_list: vec![],
+            orphan_folders: vec![],
+            orphan_list: vec![],
+            delete_permanently: false,
+            list_view: ListView::Remove,
+            state_filter: StateFilter::All,
+            item_states: HashMap::new(),
+            author_names: HashMap::new(),
+            workshop_subbed_mods: vec![],
+            workshop_preset_mods: vec![],
+            preset_queries: HashSet::new(),
+            failed_presets: vec![],
+            missing_content: vec![],
+            incomplete_presets: vec![],
+            launcher_preset_dirs: vec![],
+            collection_input: String::new(),
+            collection_error: None,
+            toggle_all_state: true,
+            sync_plan: None,
+            batch_in_progress: None,
+            action_error: None,
+            batch_total_count: 0,
+            batch_progress: Arc::new(AtomicU32::new(0)),
+            loading_page_count: None,
+            loading_progress: Arc::new(AtomicU32::new(0)),
+            fetch_error: None,
+            steam_status: SteamStatus::Offline,
+            verifying: false,
+            size_mismatches: HashMap::new(),
+            mod_columns: vec![],
+        };
+
+        // keep trying in the background, so starting steam after AMDU is enough
+        let retry = match amdu.workshop.is_none() {
+            true => amdu.schedule_retry(),
+            false => Task::none(),
+        };
         (
-            Self {
-                workshop: ws,
-                error: err,
-                parser: PresetParser::new(),
-                mod_selection_list: vec![],
-                workshop_subbed_mods: vec![],
-                toggle_all_state: true,
-                unsub_in_progress: false,
-                unsub_total_count: 0,
-                unsub_progress: Arc::new(AtomicU32::new(0)),
-            },
-            Task::perform(init(), Message::Init),
+            amdu,
+            Task::batch(vec![Task::perform(init(), Message::Init), retry]),
         )
     }
 
     fn subscription(&self) -> Subscription<Message> {
         Subscription::batch(vec![
             event::listen().map(Message::EventOccurred),
-            // this is not the most optimal way to do it, but the polling ticker only runs during unsub progress... so should be fine
-            match self.unsub_in_progress {
+            // this is not the most optimal way to do it, but the polling ticker only runs during unsub/sub progress or loading... so should be fine
+            match self.batch_in_progress.is_some() || self.loading_page_count.is_some() {
                 false => Subscription::none(),
-                true => time::every(Duration::from_millis(10)).map(Message::UnsubProgress),
+                true => time::every(Duration::from_millis(10)).map(|_| Message::BatchProgress),
+            },
+            // heartbeat of the steam callbacks, for the connection indicator
+            match self.workshop.is_some() {
+                false => Subscription::none(),
+                true => time::every(Duration::from_secs(1)).map(|_| Message::CheckSteam),
             },
         ])
     }
@@ -108,11 +397,9 @@ impl Amdu {
                 // if window close event, we drop workshop as this will trigger cleanup for the spawned thread there
                 if let Event::Window(window::Event::CloseRequested) = event {
                     // stop workshop thread
-                    self.workshop
-                        .as_ref()
-                        .unwrap()
-                        .thread_shutdown_signal
-                        .cancel();
+                    if let Some(ws) = &self.workshop {
+                        ws.shutdown();
+                    }
                     // close window
                     window::get_latest().and_then(window::close)
                 } else {
@@ -120,13 +407,21 @@ impl Amdu {
                 }
             }
             Message::Init(Ok(_)) => {
-                // init called as app is started
+                // init called as app is started, look for the launchers own presets
+                let game_dir = self
+                    .workshop
+                    .as_ref()
+                    .and_then(|ws| ws.game_install_dir());
+                self.launcher_preset_dirs = launcher::find_preset_dirs(game_dir.as_deref());
+                let discover = Task::perform(
+                    find_launcher_presets(self.launcher_preset_dirs.clone()),
+                    Message::LauncherPresetsFound,
+                );
+
                 // Don't fetch anything if workshop could not be initialized
-                match self.workshop.clone() {
-                    Some(ws) => {
-                        Task::perform(load_subscribed_mods(ws), Message::SubscribedModsFetched)
-                    }
-                    None => Task::none(),
+                match self.workshop.is_some() {
+                    true => Task::batch(vec![self.load_subscribed(), discover]),
+                    false => discover,
                 }
             }
             Message::Init(Err(e)) => {
@@ -134,10 +429,13 @@ impl Amdu {
                 Task::none()
             }
             Message::SubscribedModsFetched(result) => {
+                self.loading_page_count = None;
                 match result {
                     Ok(mods) => {
+                        self.fetch_error = None;
                         self.workshop_subbed_mods = mods.to_vec();
 
+                        // names, missing content and the diff all depend on the subscribed mods
                         Task::batch(vec![
                             Task::perform(
                                 calculate_local_file_size(
@@ -146,48 +444,140 @@ impl Amdu {
                                 ),
                                 Message::LocalFileSizeFetched,
                             ),
-                            Task::perform(
-                                calculate_diff_mods(
-                                    self.parser.get_modpresets(),
-                                    self.workshop_subbed_mods.clone(),
-                                ),
-                                Message::UpdateSelectionView,
-                            ),
+                            self.presets_changed(),
                         ])
                     }
-                    Err(_) => Task::none(),
+                    Err(e) => {
+                        println!("Failed fetching subscribed mods: {}", e);
+                        self.fetch_error = Some(e.to_string());
+                        Task::none()
+                    }
                 }
             }
-            Message::UpdateSelectionView(diff_mods) => {
+            Message::ReloadSubscribed => match self.workshop.is_some() {
+                true => self.load_subscribed(),
+                false => Task::none(),
+            },
+            Message::UpdateSelectionView(diff) => {
                 // get diff, not calling as async as this is just straight vector diff and thus quick
 
                 let mut mod_rows = vec![];
-                for item in diff_mods.iter() {
+                for item in diff.remove.iter() {
                     let row = ModRow::new(
                         item.id,
                         item.name.clone(),
                         item.url.clone(),
                         item.local_filesize,
                         true,
-                    );
+                    )
+                    .with_details(item.details.clone())
+                    .with_warning(size_warning(&self.size_mismatches, item.id));
                     mod_rows.push(row);
                 }
                 self.mod_selection_list = mod_rows;
 
+                // kept and missing mods are only listed, so they start unselected
+                let size_mismatches = &self.size_mismatches;
+                let preset_rows = |mods: &[diff::PresetMod], marker: &str| -> Vec<ModRow> {
+                    mods.iter()
+                        .map(|kept| {
+                            // mods no preset lists are only there because another mod needs them
+                            let note = kept
+                                .presets
+                                .is_empty()
+                                .then(|| format!("{} {}", marker, kept.required_by.join(", ")));
+                            ModRow::new(
+                                kept.item.id,
+                                kept.item.name.clone(),
+                                kept.item.url.clone(),
+                                kept.item.local_filesize,
+                                false,
+                            )
+                            .with_presets(kept.presets.clone())
+                            .with_details(kept.item.details.clone())
+                            .with_note(note)
+                            .with_warning(size_warning(size_mismatches, kept.item.id))
+                        })
+                        .collect()
+                };
+                self.kept_list = preset_rows(&diff.kept, "Kept as dependency of");
+                self.not_subscribed_list =
+                    preset_rows(&diff.not_subscribed, "Needed as dependency of");
+                self.incomplete_presets = diff.incomplete.clone();
+                self.apply_item_states();
+                self.refresh_author_names();
+
+                Task::none()
+            }
+            Message::PresetModsFetched(asked, result) => match result {
+                Ok(mods) => {
+                    self.workshop_preset_mods.extend(mods.iter().cloned());
+                    self.presets_changed()
+                }
+                Err(e) => {
+                    println!("Failed resolving preset mod names: {}", e);
+                    // ask again on the next change, the workshop might be back by then
+                    for id in asked {
+                        self.preset_queries.remove(&id);
+                    }
+                    Task::none()
+                }
+            },
+            Message::ShowList(view) => {
+                self.list_view = view;
+                Task::none()
+            }
+            Message::FilterState(filter) => {
+                self.state_filter = filter;
+                Task::none()
+            }
+            Message::DownloadSelected => {
+                let Some(ws) = self.workshop.clone() else {
+                    return Task::none();
+                };
+                let filter = self.state_filter;
+                for item in self
+                    .shown_list()
+                    .iter()
+                    .filter(|item| item.selected && filter.matches(item))
+                {
+                    if let Err(e) = ws.download(item.id) {
+                        println!("Failed starting download of {}: {}", item.name, e);
+                    }
+                }
+                // show them as pending right away
+                self.refresh_item_states();
+                Task::none()
+            }
+            Message::ToggleColumn(column, shown) => {
+                // keep the columns in a fixed order, whatever order they are ticked in
+                self.mod_columns = Column::ALL
+                    .into_iter()
+                    .filter(|c| match *c == column {
+                        true => shown,
+                        false => self.mod_columns.contains(c),
+                    })
+                    .collect();
+                self.refresh_author_names();
                 Task::none()
             }
             Message::LocalFileSizeFetched(result) => {
                 match result {
                     Ok(mods) => {
                         self.workshop_subbed_mods = mods.to_vec();
+                        self.refresh_item_states();
                         // as we have updated data source now, update selection view by recalc
-                        Task::perform(
-                            calculate_diff_mods(
-                                self.parser.get_modpresets(),
-                                self.workshop_subbed_mods.clone(),
+                        Task::batch(vec![
+                            Task::perform(
+                                calculate_diff_mods(
+                                    self.parser.get_modpresets(),
+                                    self.workshop_subbed_mods.clone(),
+                                    self.workshop_preset_mods.clone(),
+                                ),
+                                Message::UpdateSelectionView,
                             ),
-                            Message::UpdateSelectionView,
-                        )
+                            self.scan_orphans(),
+                        ])
                     }
                     Err(e) => {
                         println!("Failed fetching local install sizes with error: {:?}", e);
@@ -195,12 +585,33 @@ impl Amdu {
                     }
                 }
             }
+            Message::OrphansFound(orphans) => {
+                // the folder name is all we know, the workshop forgets items we are not subscribed to
+                self.orphan_list = orphans
+                    .iter()
+                    .map(|orphan| {
+                        ModRow::new(
+                            orphan.id,
+                            orphan.id.to_string(),
+                            Mod::from_workshop_id(orphan.id, None).url,
+                            orphan.size,
+                            true,
+                        )
+                        .with_note(Some(orphan.path.display().to_string()))
+                    })
+                    .collect();
+                self.orphan_folders = orphans.to_vec();
+                Task::none()
+            }
             Message::OpenFileDialog => {
                 println!("opening file dialog btn pressed");
-                Task::perform(pick_files(), Message::FilesPicked)
+                Task::perform(
+                    pick_files(self.launcher_preset_dirs.first().cloned()),
+                    Message::FilesPicked,
+                )
             }
             Message::FilesPicked(Ok(content)) => Task::perform(
-                PresetParser::load_files_async(content.to_vec()),
+                PresetParser::load_files_async(content.to_vec(), self.mod_search_dirs()),
                 Message::FilesParsed,
             ),
             Message::FilesPicked(Err(error)) => {
@@ -208,73 +619,259 @@ impl Amdu {
 
                 Task::none()
             }
-            Message::FilesParsed(Ok(data)) => {
-                // save parsed to own state
-                self.parser.set_modpresets(data.to_vec()).unwrap();
+            // presets picked by hand are the ones to keep, so they start enabled
+            Message::FilesParsed(results) => self.store_parsed_presets(&results, true),
+            Message::LauncherPresetsFound(paths) => Task::perform(
+                PresetParser::load_files_async(paths.to_vec(), self.mod_search_dirs()),
+                Message::LauncherPresetsParsed,
+            ),
+            // found presets are only listed, the user ticks the ones to keep
+            Message::LauncherPresetsParsed(results) => self.store_parsed_presets(&results, false),
+            Message::TogglePreset(index, enabled) => {
+                self.parser.set_preset_enabled(index, enabled);
+                self.presets_changed()
+            }
+            Message::CollectionInputChanged(input) => {
+                self.collection_input = input;
+                Task::none()
+            }
+            Message::AddCollection => {
+                let id = match parse_workshop_ref(&self.collection_input) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        self.collection_error = Some(e.to_string());
+                        return Task::none();
+                    }
+                };
+                self.collection_error = None;
 
-                Task::perform(
-                    calculate_diff_mods(
-                        self.parser.get_modpresets(),
-                        self.workshop_subbed_mods.clone(),
+                match self.workshop.clone() {
+                    Some(ws) => Task::perform(
+                        async move { collections::resolve_collection(ws.as_ref(), id).await },
+                        move |result| Message::CollectionResolved(id, result),
                     ),
-                    Message::UpdateSelectionView,
-                )
+                    None => Task::none(),
+                }
+            }
+            Message::CollectionResolved(id, Ok(preset)) => {
+                self.collection_input.clear();
+                // a collection is added on purpose, so it is kept like picked files
+                self.parser
+                    .add_modpresets(vec![(PresetSource::Collection(id), preset)], true)
+                    .unwrap();
+                self.presets_changed()
+            }
+            Message::CollectionResolved(id, Err(e)) => {
+                println!("Failed resolving collection {}: {}", id, e);
+                self.collection_error = Some(e.to_string());
+                Task::none()
             }
-            Message::FilesParsed(Err(error)) => {
-                println!("Error on files parsed: {:?}", error);
+            Message::ExportMergedPresets => {
+                let merged =
+                    ModPreset::merge("AMDU Merged".to_string(), &self.parser.get_modpresets());
+                Task::perform(save_preset_file(merged), Message::PresetExported)
+            }
+            Message::ExportSubscribedMods => {
+                let subscribed = ModPreset::from_mods(
+                    "AMDU Subscribed".to_string(),
+                    self.workshop_subbed_mods.clone(),
+                );
+                Task::perform(save_preset_file(subscribed), Message::PresetExported)
+            }
+            Message::PresetExported(result) => {
+                match result {
+                    Ok(path) => println!("Exported preset to {:?}", path),
+                    Err(e) => println!("Error on exporting preset: {}", e),
+                }
                 Task::none()
             }
             Message::List(index, msg) => {
+                let list = self.shown_list_mut();
                 match msg {
                     RowMessage::ToggleSelection(toggle) => {
-                        self.mod_selection_list[index].selected = toggle;
+                        list[index].selected = toggle;
                         Task::none()
                     }
                     RowMessage::ModPressed => {
                         // we do the same as toggle selection
-                        self.mod_selection_list[index].selected =
-                            !self.mod_selection_list[index].selected;
+                        list[index].selected = !list[index].selected;
 
                         Task::none()
                     }
                 }
             }
             Message::UnsubSelected => {
-                self.unsub_in_progress = true;
-                self.unsub_total_count = self
-                    .mod_selection_list
-                    .iter()
-                    .filter(|item| item.selected)
-                    .count() as u32;
+                self.start_batch(BatchAction::Unsubscribe);
 
                 Task::perform(
                     unsub_selected_mods(
-                        self.mod_selection_list.clone(),
+                        self.visible_rows(&self.mod_selection_list),
+                        self.workshop.clone().unwrap(),
+                        self.batch_progress.clone(),
+                    ),
+                    Message::BatchFinished,
+                )
+            }
+            Message::SubscribeSelected => {
+                self.start_batch(BatchAction::Subscribe);
+
+                Task::perform(
+                    sub_selected_mods(
+                        self.visible_rows(&self.not_subscribed_list),
+                        self.workshop.clone().unwrap(),
+                        self.batch_progress.clone(),
+                    ),
+                    Message::BatchFinished,
+                )
+            }
+            Message::DeleteSelectedFolders => {
+                self.start_batch(BatchAction::DeleteFolders);
+
+                let folders = self
+                    .orphan_folders
+                    .iter()
+                    .zip(&self.orphan_list)
+                    .filter(|(_, row)| row.selected && self.state_filter.matches(row))
+                    .map(|(orphan, _)| orphan.path.clone())
+                    .collect();
+                Task::perform(
+                    orphans::delete_folders(
+                        folders,
+                        self.delete_permanently,
+                        self.batch_progress.clone(),
+                    ),
+                    Message::BatchFinished,
+                )
+            }
+            Message::ToggleDeletePermanently(permanently) => {
+                self.delete_permanently = permanently;
+                // offline, removing a mod deletes its folder as well
+                if let Some(ws) = &self.workshop {
+                    ws.set_delete_permanently(permanently);
+                }
+                Task::none()
+            }
+            Message::PlanSync => {
+                self.action_error = None;
+                Task::perform(
+                    plan_sync(
+                        self.parser.get_modpresets(),
+                        self.workshop_subbed_mods.clone(),
+                        self.workshop_preset_mods.clone(),
                         self.workshop.clone().unwrap(),
-                        self.unsub_progress.clone(),
                     ),
-                    Message::UnsubbedSelectedMods,
+                    Message::SyncPlanned,
                 )
             }
-            Message::UnsubbedSelectedMods(_) => {
-                self.unsub_in_progress = false;
+            Message::SyncPlanned(result) => {
+                match result {
+                    Ok(plan) => self.sync_plan = Some(plan),
+                    Err(e) => {
+                        println!("Failed planning sync: {}", e);
+                        self.action_error = Some(format!("Failed planning sync: {}", e));
+                    }
+                }
+                Task::none()
+            }
+            Message::CancelSync => {
+                self.sync_plan = None;
+                Task::none()
+            }
+            Message::ConfirmSync => {
+                let Some(plan) = self.sync_plan.clone() else {
+                    return Task::none();
+                };
+                self.start_batch(BatchAction::Sync);
+
                 Task::perform(
-                    load_subscribed_mods(self.workshop.clone().unwrap()),
-                    Message::SubscribedModsFetched,
+                    sync_mods(
+                        plan,
+                        self.workshop.clone().unwrap(),
+                        self.batch_progress.clone(),
+                    ),
+                    Message::BatchFinished,
                 )
             }
-            Message::UnsubProgress(_) => {
-                // self.unsub_progress = *progress;
+            Message::BatchFinished(result) => {
+                if let Err(e) = &result {
+                    println!("Error while changing subscriptions: {:?}", e);
+                }
+                self.action_error = result.err();
+                self.batch_in_progress = None;
+                self.sync_plan = None;
+                self.load_subscribed()
+            }
+            Message::BatchProgress => {
                 // just ticking gui update...
                 Task::none()
             }
+            Message::Retry => {
+                // a manual retry starts the backoff over, and cancels the pending automatic one
+                self.retry_generation += 1;
+                self.retry_attempt = 0;
+                self.reconnect()
+            }
+            Message::AutoRetry(generation) => {
+                match generation == self.retry_generation && self.workshop.is_none() {
+                    true => self.reconnect(),
+                    false => Task::none(),
+                }
+            }
+            Message::ToggleAutoRetry(enabled) => {
+                self.auto_retry = enabled;
+                self.retry_generation += 1;
+                self.schedule_retry()
+            }
+            Message::CheckSteam => {
+                if let Some(ws) = &self.workshop {
+                    self.steam_status = ws.steam_status();
+                }
+                // follow running downloads, the states don't change on their own otherwise
+                let downloading = self.item_states.values().any(|(state, _)| {
+                    state.intersects(ItemState::DOWNLOADING | ItemState::DOWNLOAD_PENDING)
+                });
+                if downloading {
+                    self.refresh_item_states();
+                }
+                // steam answers author lookups a moment after they are asked for
+                self.refresh_author_names();
+                Task::none()
+            }
+            Message::VerifySizes => {
+                self.verifying = true;
+                Task::perform(
+                    verify_install_sizes(
+                        self.workshop_subbed_mods.clone(),
+                        self.workshop.clone().unwrap(),
+                    ),
+                    Message::SizesVerified,
+                )
+            }
+            Message::SizesVerified(mismatches) => {
+                self.verifying = false;
+                self.size_mismatches = mismatches.as_ref().clone();
+                // only the warnings change, the selection stays as the user left it
+                let size_mismatches = &self.size_mismatches;
+                self.mod_selection_list
+                    .iter_mut()
+                    .chain(self.kept_list.iter_mut())
+                    .chain(self.not_subscribed_list.iter_mut())
+                    .for_each(|row| row.warning = size_warning(size_mismatches, row.id));
+                Task::none()
+            }
             Message::ToggleAll => {
                 // toggle state
                 self.toggle_all_state = !self.toggle_all_state;
 
-                // update selection
-                for val in self.mod_selection_list.iter_mut() {
-                    val.selected = self.toggle_all_state;
+                // update selection, mods hidden by the filter are left alone
+                let toggle_all_state = self.toggle_all_state;
+                let filter = self.state_filter;
+                for val in self
+                    .shown_list_mut()
+                    .iter_mut()
+                    .filter(|item| filter.matches(item))
+                {
+                    val.selected = toggle_all_state;
                 }
 
                 Task::none()
@@ -282,6 +879,280 @@ impl Amdu {
         }
     }
 
+    /// Tries connecting to the workshop again, and loads everything like at startup if it works
+    fn reconnect(&mut self) -> Task<Message> {
+        let Ok(mode) = &self.launch else {
+            return Task::none();
+        };
+
+        match mode.connect() {
+            Ok(ws) => {
+                self.workshop = Some(ws);
+                self.error.clear();
+                self.retry_delay = None;
+                Task::perform(init(), Message::Init)
+            }
+            Err(e) => {
+                println!("Workshop Error: {:?}", e);
+                self.error = e;
+                self.retry_attempt += 1;
+                self.schedule_retry()
+            }
+        }
+    }
+
+    /// Queues the next automatic reconnect, waiting longer after every failed attempt
+    fn schedule_retry(&mut self) -> Task<Message> {
+        if !self.auto_retry || self.launch.is_err() || self.workshop.is_some() {
+            self.retry_delay = None;
+            return Task::none();
+        }
+
+        let delay = RETRY_BACKOFF_START
+            .saturating_mul(2u32.saturating_pow(self.retry_attempt))
+            .min(RETRY_BACKOFF_MAX);
+        self.retry_delay = Some(delay);
+
+        let generation = self.retry_generation;
+        Task::perform(tokio::time::sleep(delay), move |_| {
+            Message::AutoRetry(generation)
+        })
+    }
+
+    /// Whether the mods come from folders on disk, which can be removed but not subscribed to
+    fn is_offline(&self) -> bool {
+        matches!(self.launch, Ok(LaunchMode::Offline(_)))
+    }
+
+    /// Whether the mods come from a fixture, whose install folders do not exist on this machine
+    fn is_demo(&self) -> bool {
+        matches!(self.launch, Ok(LaunchMode::Demo(_)))
+    }
+
+    /// Fetches the metadata of all subscribed mods, showing progress per workshop page
+    fn load_subscribed(&mut self) -> Task<Message> {
+        let workshop = self.workshop.clone().unwrap();
+        self.loading_page_count =
+            Some(backend::page_count(workshop.subscribed_items().len()) as u32);
+        self.loading_progress.store(0, Ordering::Relaxed);
+
+        Task::perform(
+            load_subscribed_mods(workshop, self.loading_progress.clone()),
+            Message::SubscribedModsFetched,
+        )
+    }
+
+    /// Looks through the workshop content folders for items we are no longer subscribed to
+    fn scan_orphans(&self) -> Task<Message> {
+        let Some(ws) = self.workshop.clone() else {
+            return Task::none();
+        };
+
+        Task::perform(
+            async move { Arc::new(orphans::find_orphans(ws).await) },
+            Message::OrphansFound,
+        )
+    }
+
+    fn start_batch(&mut self, action: BatchAction) {
+        let selected = |rows: &[ModRow]| {
+            rows.iter()
+                .filter(|item| item.selected && self.state_filter.matches(item))
+                .count()
+        };
+        self.batch_total_count = match action {
+            BatchAction::Unsubscribe => selected(&self.mod_selection_list),
+            BatchAction::Subscribe => selected(&self.not_subscribed_list),
+            BatchAction::Sync => self.sync_plan.as_ref().map_or(0, |plan| plan.len()),
+            BatchAction::DeleteFolders => selected(&self.orphan_list),
+        } as u32;
+        self.batch_in_progress = Some(action);
+        self.action_error = None;
+        self.batch_progress.store(0, Ordering::Relaxed);
+    }
+
+    /// Rows the state filter shows, the ones actions apply to
+    fn visible_rows(&self, rows: &[ModRow]) -> Vec<ModRow> {
+        rows.iter()
+            .filter(|item| self.state_filter.matches(item))
+            .cloned()
+            .collect()
+    }
+
+    /// Asks steam what it is doing with every subscribed mod
+    fn refresh_item_states(&mut self) {
+        let Some(ws) = &self.workshop else {
+            return;
+        };
+        self.item_states = self
+            .workshop_subbed_mods
+            .iter()
+            .map(|item| {
+                let state = ws.item_state(item.id);
+                let progress = state
+                    .contains(ItemState::DOWNLOADING)
+                    .then(|| ws.download_progress(item.id))
+                    .flatten();
+                (item.id, (state, progress))
+            })
+            .collect();
+        self.apply_item_states();
+    }
+
+    fn apply_item_states(&mut self) {
+        for row in self
+            .mod_selection_list
+            .iter_mut()
+            .chain(self.kept_list.iter_mut())
+        {
+            if let Some((state, progress)) = self.item_states.get(&row.id) {
+                row.state = Some(*state);
+                row.download_progress = *progress;
+            }
+        }
+    }
+
+    /// Names the authors of the listed mods, only while their column is shown as every unknown
+    /// author is a lookup on steam
+    fn refresh_author_names(&mut self) {
+        let Some(ws) = &self.workshop else {
+            return;
+        };
+        if !self.mod_columns.contains(&Column::Author) {
+            return;
+        }
+        for row in self
+            .mod_selection_list
+            .iter_mut()
+            .chain(self.kept_list.iter_mut())
+            .chain(self.not_subscribed_list.iter_mut())
+            .filter(|row| row.author.is_none())
+        {
+            let Some(owner) = row.details.as_ref().map(|details| details.owner) else {
+                continue;
+            };
+            if owner == 0 {
+                continue;
+            }
+            let name = match self.author_names.get(&owner) {
+                Some(name) => Some(name.clone()),
+                None => ws.author_name(owner),
+            };
+            if let Some(name) = name {
+                self.author_names.insert(owner, name.clone());
+                row.author = Some(name);
+            }
+        }
+    }
+
+    fn shown_list(&self) -> &Vec<ModRow> {
+        match self.list_view {
+            ListView::Remove => &self.mod_selection_list,
+            ListView::Kept => &self.kept_list,
+            ListView::NotSubscribed => &self.not_subscribed_list,
+            ListView::Orphaned => &self.orphan_list,
+        }
+    }
+
+    fn shown_list_mut(&mut self) -> &mut Vec<ModRow> {
+        match self.list_view {
+            ListView::Remove => &mut self.mod_selection_list,
+            ListView::Kept => &mut self.kept_list,
+            ListView::NotSubscribed => &mut self.not_subscribed_list,
+            ListView::Orphaned => &mut self.orphan_list,
+        }
+    }
+
+    fn store_parsed_presets(
+        &mut self,
+        results: &[PresetFileResult],
+        enabled: bool,
+    ) -> Task<Message> {
+        // keep the presets that parsed, and remember which files failed so we can show why
+        let mut presets = vec![];
+        for file in results {
+            self.failed_presets.retain(|(path, _)| *path != file.path);
+            match &file.result {
+                Ok(preset) => presets.push((PresetSource::File(file.path.clone()), preset.clone())),
+                Err(e) => {
+                    println!("Failed parsing preset {:?}: {}", file.path, e);
+                    self.failed_presets.push((file.path.clone(), e.clone()));
+                }
+            }
+        }
+
+        // save parsed to own state
+        self.parser.add_modpresets(presets, enabled).unwrap();
+        self.presets_changed()
+    }
+
+    /// Folders `-mod=` lists are resolved against, the game folder and the launchers workshop links
+    fn mod_search_dirs(&self) -> Vec<PathBuf> {
+        match self
+            .workshop
+            .as_ref()
+            .and_then(|ws| ws.game_install_dir())
+        {
+            Some(dir) => vec![dir.join("!Workshop"), dir],
+            None => vec![],
+        }
+    }
+
+    /// Recalculates everything that depends on which presets are kept
+    fn presets_changed(&mut self) -> Task<Message> {
+        // a planned sync is for the old presets
+        self.sync_plan = None;
+        self.parser.resolve_names(&self.workshop_subbed_mods);
+        self.parser.resolve_names(&self.workshop_preset_mods);
+        self.missing_content = match &self.workshop {
+            Some(ws) => find_missing_content(&self.parser.get_modpresets(), ws.as_ref()),
+            None => vec![],
+        };
+
+        // id lists, csv files and .preset2 files only have ids, and mods we are not subscribed to
+        // only tell what they depend on through the workshop. Ask it for both
+        let resolve_names = match self.workshop.clone() {
+            Some(ws) => {
+                let subscribed: HashSet<u64> = self
+                    .workshop_subbed_mods
+                    .iter()
+                    .map(|item| item.id)
+                    .collect();
+                let mut wanted = self.parser.unnamed_mod_ids();
+                wanted.extend(self.parser.get_all_mod_ids_unique().unwrap_or_default());
+                wanted.extend(
+                    self.workshop_preset_mods
+                        .iter()
+                        .flat_map(|item| item.dependencies.iter().copied()),
+                );
+                let unknown: Vec<u64> = wanted
+                    .into_iter()
+                    .filter(|id| !subscribed.contains(id))
+                    .filter(|id| self.preset_queries.insert(*id))
+                    .collect();
+                match unknown.is_empty() {
+                    true => Task::none(),
+                    false => Task::perform(query_preset_mods(ws, unknown.clone()), move |result| {
+                        Message::PresetModsFetched(unknown.clone(), result)
+                    }),
+                }
+            }
+            None => Task::none(),
+        };
+
+        Task::batch(vec![
+            Task::perform(
+                calculate_diff_mods(
+                    self.parser.get_modpresets(),
+                    self.workshop_subbed_mods.clone(),
+                    self.workshop_preset_mods.clone(),
+                ),
+                Message::UpdateSelectionView,
+            ),
+            resolve_names,
+        ])
+    }
+
     fn view(&self) -> Element<'_, Message> {
         // ERROR PAGE
         if self.workshop.is_none() {
@@ -303,6 +1174,24 @@ impl Amdu {
                     .align_y(Vertical::Center)]
                 .spacing(10)
                 .height(Length::FillPortion(400)),
+                row![
+                    button("Retry")
+                        .padding(10)
+                        .on_press_maybe(self.launch.is_ok().then_some(Message::Retry)),
+                    checkbox("Retry automatically", self.auto_retry)
+                        .on_toggle_maybe(self.launch.is_ok().then_some(Message::ToggleAutoRetry)),
+                    match (self.auto_retry, self.retry_delay) {
+                        (true, Some(delay)) => text(format!(
+                            "Next try in {}s (retry {})",
+                            delay.as_secs(),
+                            self.retry_attempt + 1
+                        ))
+                        .style(text::secondary),
+                        _ => text(""),
+                    },
+                ]
+                .spacing(20)
+                .align_y(Vertical::Center),
                 row![
                     horizontal_space(),
                     text(format!("v{}", VERSION))
@@ -324,26 +1213,86 @@ impl Amdu {
             text("Load presets you wish to keep")
                 .align_x(Horizontal::Center)
                 .align_y(Vertical::Top),
-            Space::with_height(Length::Fixed(15.0)),
+            Space::with_height(Length::Fixed(8.0)),
             button("Load Presets")
                 .padding(10)
                 .on_press(Message::OpenFileDialog),
+            Space::with_height(Length::Fixed(8.0)),
+            row![
+                text_input("Collection ID or URL", &self.collection_input)
+                    .on_input(Message::CollectionInputChanged)
+                    .on_submit(Message::AddCollection)
+                    .width(170),
+                button("Add").padding(5).on_press(Message::AddCollection),
+            ]
+            .spacing(5),
+            Space::with_height(Length::Fixed(8.0)),
+            row![
+                button("Export Merged").padding(5).on_press_maybe(
+                    (!self.parser.get_modpresets().is_empty())
+                        .then_some(Message::ExportMergedPresets)
+                ),
+                button("Export Subscribed").padding(5).on_press_maybe(
+                    (!self.workshop_subbed_mods.is_empty())
+                        .then_some(Message::ExportSubscribedMods)
+                ),
+            ]
+            .spacing(5),
         ]
             .padding([5, 5])
             .align_x(Horizontal::Center)
-            .height(150);
+            .height(190);
 
-        // get loaded presets name only
-        let scrollable_presets = scrollable(
-            self.parser.get_modpresets().iter().fold(
-                column![]
-                    .spacing(6)
-                    .width(Length::Fill),
-                |col, i| col.push(text(i.name.clone()).align_x(Horizontal::Center)),
+        // loaded presets with a checkbox to keep them, followed by the files that failed to load
+        let loaded_presets = self.parser.get_loaded_presets().iter().enumerate().fold(
+            column![].spacing(6).width(Length::Fill),
+            |col, (i, loaded)| {
+                let col = col.push(
+                    checkbox(loaded.preset.name.clone(), loaded.enabled)
+                        .on_toggle(move |toggle| Message::TogglePreset(i, toggle)),
+                );
+                // rows of the preset we could not use
+                loaded.preset.warnings.iter().fold(col, |col, warning| {
+                    col.push(text(warning.to_string()).style(text::secondary).size(12))
+                })
+            },
+        );
+        let failed_presets = self
+            .failed_presets
+            .iter()
+            .fold(loaded_presets, |col, (path, e)| {
+                let file = path
+                    .file_name()
+                    .unwrap_or(path.as_os_str())
+                    .to_string_lossy();
+                col.push(
+                    text(format!("{}: {}", file, e))
+                        .style(text::danger)
+                        .align_x(Horizontal::Center),
+                )
+            });
+        let failed_presets = match &self.collection_error {
+            Some(e) => failed_presets.push(
+                text(format!("Collection: {}", e))
+                    .style(text::danger)
+                    .align_x(Horizontal::Center),
             ),
+            None => failed_presets,
+        };
+        let scrollable_presets = scrollable(
+            self.missing_content
+                .iter()
+                .chain(&self.incomplete_presets)
+                .fold(failed_presets, |col, warning| {
+                    col.push(
+                        text(format!("Warning: {}", warning))
+                            .style(text::primary)
+                            .align_x(Horizontal::Center),
+                    )
+                }),
         )
         .width(Length::Fill)
-        .height(100);
+        .height(140);
 
         let presets_loaded = column![
             text("Loaded Presets")
@@ -357,19 +1306,19 @@ impl Amdu {
         ]
             .padding([5, 5])
             .align_x(Horizontal::Center)
-            .height(150)
+            .height(190)
             .width(Length::FillPortion(200));
 
         // stats
         let selected_mods_count = self
             .mod_selection_list
             .iter()
-            .filter(|item| item.selected)
+            .filter(|item| item.selected && self.state_filter.matches(item))
             .count();
         let subscribed_mods_local_size_sum: u64 = self
             .mod_selection_list
             .iter()
-            .filter(|item| item.selected)
+            .filter(|item| item.selected && self.state_filter.matches(item))
             .map(|item| item.file_size)
             .sum();
 
@@ -395,11 +1344,29 @@ impl Amdu {
         ]
         .padding([5, 5])
         .spacing(20)
-        .height(150)
+        .height(190)
         .width(300);
 
+        // the action follows the list that is shown, kept mods have nothing to do
+        let (action_label, action) = match self.list_view {
+            // without steam, removing a mod means removing its folder
+            ListView::Remove if self.is_offline() => match self.delete_permanently {
+                true => ("Delete Selected Mods", Some(Message::UnsubSelected)),
+                false => ("Trash Selected Mods", Some(Message::UnsubSelected)),
+            },
+            ListView::Remove => ("Unsub Selected Mods", Some(Message::UnsubSelected)),
+            ListView::NotSubscribed if self.is_offline() => ("Subscribe Selected Mods", None),
+            ListView::NotSubscribed => {
+                ("Subscribe Selected Mods", Some(Message::SubscribeSelected))
+            }
+            ListView::Kept => ("Unsub Selected Mods", None),
+            ListView::Orphaned => (
+                "Delete Selected Folders",
+                Some(Message::DeleteSelectedFolders),
+            ),
+        };
         let mut unsub_button = button(
-            row![text("Unsub Selected Mods")
+            row![text(action_label)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .align_y(Vertical::Center)
@@ -407,41 +1374,126 @@ impl Amdu {
         )
         .padding([5, 5])
         .width(150)
-        .height(150);
+        .height(190);
 
-        if !self.mod_selection_list.is_empty() {
-            unsub_button = unsub_button.on_press(Message::UnsubSelected);
+        if !self.shown_list().is_empty() && self.batch_in_progress.is_none() {
+            unsub_button = unsub_button.on_press_maybe(action);
         }
 
-        let selection_list =
-            self.mod_selection_list
-                .iter()
-                .enumerate()
-                .fold(column![].spacing(6), |col, (i, _)| {
-                    col.push(
-                        self.mod_selection_list[i]
-                            .view()
-                            .map(move |msg| Message::List(i, msg)),
-                    )
-                });
+        let shown_list = self.shown_list();
+        let columns = &self.mod_columns;
+        let selection_list = shown_list
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| self.state_filter.matches(item))
+            .fold(column![].spacing(6), |col, (i, item)| {
+                col.push(item.view(columns).map(move |msg| Message::List(i, msg)))
+            });
+
+        // tabs to switch between the parts of the diff
+        let orphaned_size: u64 = self.orphan_list.iter().map(|row| row.file_size).sum();
+        let list_tab = |label: String, view: ListView| {
+            button(text(label))
+                .padding([5, 10])
+                .style(move |theme: &Theme, status| match self.list_view == view {
+                    true => button::primary(theme, status),
+                    false => button::secondary(theme, status),
+                })
+                .on_press(Message::ShowList(view))
+        };
+        let list_tabs = row![
+            list_tab(
+                format!("To Remove ({})", self.mod_selection_list.len()),
+                ListView::Remove
+            ),
+            list_tab(format!("Kept ({})", self.kept_list.len()), ListView::Kept),
+            list_tab(
+                format!("Not Subscribed ({})", self.not_subscribed_list.len()),
+                ListView::NotSubscribed
+            ),
+            list_tab(
+                format!(
+                    "Orphaned ({}, {})",
+                    self.orphan_list.len(),
+                    format_size(orphaned_size, DECIMAL)
+                ),
+                ListView::Orphaned
+            ),
+            horizontal_space(),
+            pick_list(
+                &StateFilter::ALL[..],
+                Some(self.state_filter),
+                Message::FilterState
+            ),
+            text("Columns:"),
+        ]
+        .spacing(5)
+        .align_y(Vertical::Center);
+        // optional workshop detail columns of the list
+        let list_tabs = Column::ALL.into_iter().fold(list_tabs, |row, column| {
+            row.push(
+                checkbox(column.label(), self.mod_columns.contains(&column))
+                    .on_toggle(move |shown| Message::ToggleColumn(column, shown)),
+            )
+        });
 
-        let scrollable: Element<Message> = match self.unsub_in_progress {
-            false => scrollable(selection_list)
+        let scrollable: Element<Message> = match (self.batch_in_progress, &self.sync_plan) {
+            (None, _) if self.loading_page_count.is_some() => {
+                let page_count = self.loading_page_count.unwrap_or_default();
+                column![
+                    text(format!(
+                        "Fetching workshop info, page {} out of {}...",
+                        self.loading_progress.load(Ordering::Relaxed),
+                        page_count
+                    ))
+                    .size(30)
+                    .align_x(Horizontal::Center)
+                    .align_y(Vertical::Top),
+                    progress_bar(
+                        0.0..=page_count as f32,
+                        self.loading_progress.load(Ordering::Relaxed) as f32
+                    )
+                ]
+                .spacing(5)
+                .padding(10)
+                .into()
+            }
+            (None, _) if self.fetch_error.is_some() => column![
+                text(format!(
+                    "Failed fetching subscribed mods: {}",
+                    self.fetch_error.as_deref().unwrap_or_default()
+                ))
+                .style(text::danger),
+                button("Retry")
+                    .padding(10)
+                    .on_press(Message::ReloadSubscribed),
+            ]
+            .spacing(10)
+            .padding(10)
+            .into(),
+            (None, Some(plan)) => sync_plan_view(plan),
+            (None, None) => scrollable(selection_list)
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .into(),
-            true => column![
+            (Some(action), _) => column![
                 text(format!(
-                    "Unsubbing mod {} out of {}...",
-                    self.unsub_progress.load(Ordering::Relaxed),
-                    self.unsub_total_count
+                    "{} mod {} out of {}...",
+                    match action {
+                        BatchAction::Unsubscribe => "Unsubbing",
+                        BatchAction::Subscribe => "Subscribing to",
+                        BatchAction::Sync => "Syncing",
+                        BatchAction::DeleteFolders => "Deleting folder of",
+                    },
+                    self.batch_progress.load(Ordering::Relaxed),
+                    self.batch_total_count
                 ))
                 .size(30)
                 .align_x(Horizontal::Center)
                 .align_y(Vertical::Top),
                 progress_bar(
-                    0.0..=self.unsub_total_count as f32,
-                    self.unsub_progress.load(Ordering::Relaxed) as f32
+                    0.0..=self.batch_total_count as f32,
+                    self.batch_progress.load(Ordering::Relaxed) as f32
                 )
             ]
             .spacing(5)
@@ -453,9 +1505,68 @@ impl Amdu {
             button("Toggle All")
                 .padding(10)
                 .on_press(Message::ToggleAll),
+            // syncing without anything to keep would unsubscribe everything
+            button("Plan Sync").padding(10).on_press_maybe(
+                (!self.parser.get_modpresets().is_empty()
+                    && self.batch_in_progress.is_none()
+                    && !self.is_offline())
+                .then_some(Message::PlanSync)
+            ),
+            // only steam can download, and only subscribed mods
+            button("Download Selected").padding(10).on_press_maybe(
+                (self.steam_status != SteamStatus::Offline
+                    && matches!(self.list_view, ListView::Remove | ListView::Kept)
+                    && self
+                        .shown_list()
+                        .iter()
+                        .any(|item| item.selected && self.state_filter.matches(item)))
+                .then_some(Message::DownloadSelected)
+            ),
+            button(match self.verifying {
+                true => "Verifying...",
+                false => "Verify Sizes",
+            })
+            .padding(10)
+            .on_press_maybe(
+                // demo folders are not on disk, every mod would look like a broken download
+                (!self.verifying
+                    && !self.is_demo()
+                    && self.batch_in_progress.is_none()
+                    && !self.workshop_subbed_mods.is_empty())
+                .then_some(Message::VerifySizes)
+            ),
+            match self.size_mismatches.is_empty() {
+                true => text(""),
+                false => text(format!(
+                    "{} mods differ from the size steam reports",
+                    self.size_mismatches.len()
+                ))
+                .style(text::danger),
+            }
+            .align_y(Vertical::Bottom),
+            // orphaned folders and offline mods go to the trash, unless asked otherwise
+            match self.list_view == ListView::Orphaned
+                || (self.list_view == ListView::Remove && self.is_offline())
+            {
+                true => checkbox("Delete permanently", self.delete_permanently)
+                    .on_toggle(Message::ToggleDeletePermanently)
+                    .into(),
+                false => Element::from(Space::with_width(0)),
+            },
             horizontal_space(),
+            match self.steam_status {
+                SteamStatus::Connected => text("Steam connected").style(text::success),
+                SteamStatus::NotResponding(since) => {
+                    text(format!("Steam not responding for {}s", since.as_secs()))
+                        .style(text::danger)
+                }
+                SteamStatus::Disconnected => text("Steam disconnected").style(text::danger),
+                SteamStatus::Offline => text("Not connected to steam").style(text::secondary),
+            }
+            .align_y(Vertical::Bottom),
             text(format!("v{}", VERSION)).align_y(Vertical::Bottom)
         ]
+        .spacing(10)
         .padding(5);
 
         let content = column![
@@ -482,8 +1593,13 @@ impl Amdu {
             ]
             .spacing(8)
             // .align_items(Alignment::Center)
-            .height(160),
+            .height(200),
             horizontal_rule(38),
+            list_tabs,
+            match &self.action_error {
+                Some(e) => text(e).style(text::danger).into(),
+                None => Element::from(Space::with_height(0)),
+            },
             row![scrollable]
                 .spacing(10)
                 .height(Length::FillPortion(400)),
@@ -507,15 +1623,88 @@ impl Amdu {
     }
 }
 
+/// Confirmation of a planned sync, listing every change it makes
+fn sync_plan_view(plan: &SyncPlan) -> Element<'_, Message> {
+    let mut download = format_size(plan.download_size, DECIMAL);
+    if plan.unknown_download_sizes > 0 {
+        download = format!(
+            "{} (+{} mods of unknown size)",
+            download, plan.unknown_download_sizes
+        );
+    }
+
+    let mod_names = |title: String, mods: &[Mod]| {
+        mods.iter()
+            .fold(column![text(title).size(20)].spacing(4), |col, item| {
+                col.push(text(item.name.clone()).size(14))
+            })
+    };
+
+    column![
+        row![
+            column![
+                text(format!(
+                    "Unsubscribe from {} mods, freeing {}",
+                    plan.unsubscribe.len(),
+                    format_size(plan.freed_space, DECIMAL)
+                )),
+                text(format!(
+                    "Subscribe to {} mods, downloading about {}",
+                    plan.subscribe.len(),
+                    download
+                )),
+            ]
+            .spacing(5),
+            horizontal_space(),
+            button("Cancel").padding(10).on_press(Message::CancelSync),
+            button("Confirm Sync")
+                .padding(10)
+                .on_press_maybe((!plan.is_empty()).then_some(Message::ConfirmSync)),
+        ]
+        .spacing(10),
+        horizontal_rule(2),
+        scrollable(
+            row![
+                mod_names("To unsubscribe".to_string(), &plan.unsubscribe)
+                    .width(Length::FillPortion(1)),
+                mod_names("To subscribe".to_string(), &plan.subscribe)
+                    .width(Length::FillPortion(1)),
+            ]
+            .spacing(10)
+        )
+        .width(Length::Fill)
+        .height(Length::Fill),
+    ]
+    .spacing(10)
+    .padding(10)
+    .into()
+}
+
 #[derive(Debug, Clone)]
 enum Error {
     DialogClosed,
+    WriteFailed(String),
 }
 
-async fn pick_files() -> Result<Arc<Vec<PathBuf>>, Error> {
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::DialogClosed => write!(f, "Dialog closed"),
+            Error::WriteFailed(e) => write!(f, "Failed writing file: {}", e),
+        }
+    }
+}
+
+async fn pick_files(start_dir: Option<PathBuf>) -> Result<Arc<Vec<PathBuf>>, Error> {
+    // start in the launchers preset folder if we know it
+    let start_dir = start_dir
+        .or_else(|| std::env::var_os("HOME").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from("/"));
+
     let paths = rfd::AsyncFileDialog::new()
-        .add_filter("html", &["html"])
-        .set_directory("/")
+        .add_filter("Arma 3 Launcher presets", &["html", "preset2"])
+        .add_filter("Mod lists", &["txt", "csv"])
+        .set_directory(start_dir)
         .set_title("Pick Preset Files")
         .pick_files()
         .await
@@ -529,96 +1718,326 @@ async fn pick_files() -> Result<Arc<Vec<PathBuf>>, Error> {
     // let arc: Arc<[Path]> = vector_paths.into()
 }
 
+async fn save_preset_file(preset: ModPreset) -> Result<PathBuf, Error> {
+    let handle = rfd::AsyncFileDialog::new()
+        .add_filter("html", &["html"])
+        .set_file_name(format!("{}.html", preset.name))
+        .set_title("Save Preset File")
+        .save_file()
+        .await
+        .ok_or(Error::DialogClosed)?;
+
+    let path = handle.path().to_path_buf();
+    tokio::fs::write(&path, preset.to_launcher_html())
+        .await
+        .map_err(|e| Error::WriteFailed(e.to_string()))?;
+    Ok(path)
+}
+
+async fn find_launcher_presets(dirs: Vec<PathBuf>) -> Arc<Vec<PathBuf>> {
+    Arc::new(launcher::list_preset_files(dirs).await)
+}
+
 async fn init() -> Result<(), String> {
     // run when created, for init code
     Ok(())
 }
 
 async fn load_subscribed_mods(
-    workshop: Arc<Workshop>,
-) -> Result<Arc<Vec<Mod>>, oneshot::error::RecvError> {
-    let mods = workshop.get_subscribed_mods_info().await.unwrap();
+    workshop: Arc<dyn WorkshopBackend>,
+    progress: Arc<AtomicU32>,
+) -> Result<Arc<Vec<Mod>>, WorkshopError> {
+    let subscribed = workshop.subscribed_items();
+    let mods = query_items_paged(&workshop, subscribed, QUERY_CONCURRENCY, &progress).await?;
     let mut formatted_mods: Vec<Mod> = mods
         .iter()
         .filter(|item| !item.tags.contains(&"Scenario".to_owned()) && !item.tags.contains(&"Composition".to_owned()))
         .map(|result| Mod {
-            id: result.published_file_id.0,
+            id: result.id,
             url: result.url.clone(),
             tags: result.tags.clone(),
             name: result.title.clone(),
             local_filesize: 0,
+            details: Some(result.details.clone()),
+            // the children of a mod are the items it requires
+            dependencies: result.children.clone(),
         })
         .collect();
-    formatted_mods.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    formatted_mods.sort_by_key(|a| a.name.to_lowercase());
     Ok(Arc::new(formatted_mods))
 }
 
-async fn calculate_diff_mods(keep_sets: Vec<ModPreset>, all_mods: Vec<Mod>) -> Arc<Vec<Mod>>
-where
-    Mod: std::cmp::Ord,
-{
-    // if we have empty all_mods, we should return empty diff
-    if keep_sets.is_empty() {
-        return Arc::new(all_mods);
-    }
+/// Workshop names and dependencies of the given mods, items the workshop does not know are left out
+async fn query_preset_mods(
+    workshop: Arc<dyn WorkshopBackend>,
+    ids: Vec<u64>,
+) -> Result<Arc<Vec<Mod>>, WorkshopError> {
+    let items = query_items_paged(&workshop, ids, QUERY_CONCURRENCY, &AtomicU32::new(0)).await?;
+    Ok(Arc::new(
+        items
+            .into_iter()
+            .filter(|item| !item.title.is_empty())
+            .map(|item| Mod {
+                dependencies: item.children,
+                ..Mod::from_workshop_id(item.id, Some(item.title))
+            })
+            .collect(),
+    ))
+}
 
-    let combined_keep_mods: Vec<_> = keep_sets
-        .iter()
-        .flat_map(|item| item.mods.clone())
-        .collect();
-    let keep_mods_set = BTreeSet::from_iter(combined_keep_mods);
+/// Lists DLCs and local mods the loaded presets need, but that are not available on this machine
+fn find_missing_content(presets: &[ModPreset], workshop: &dyn WorkshopBackend) -> Vec<String> {
+    let game_dir = workshop.game_install_dir();
+    let mut missing = vec![];
 
-    let mut diff_mods: Vec<Mod> = all_mods.clone();
-    diff_mods.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    diff_mods.retain(|e| !keep_mods_set.contains(e));
+    for preset in presets {
+        for dlc in &preset.dlcs {
+            if let Some(app_id) = dlc.app_id {
+                if !workshop.is_dlc_installed(app_id) {
+                    missing.push(format!("{} requires DLC {}", preset.name, dlc.name));
+                }
+            }
+        }
+        for local in &preset.local_mods {
+            let installed = match &game_dir {
+                Some(dir) => dir.join(&local.folder).is_dir(),
+                None => false,
+            };
+            if !installed {
+                missing.push(format!(
+                    "{} requires local mod {}",
+                    preset.name, local.folder
+                ));
+            }
+        }
+    }
+    missing
+}
+
+async fn calculate_diff_mods(
+    keep_sets: Vec<ModPreset>,
+    all_mods: Vec<Mod>,
+    preset_mods: Vec<Mod>,
+) -> Arc<ModDiff> {
+    let diff = diff::diff_mods(&keep_sets, &all_mods, &preset_mods);
 
     // sleep we need due to bug on windows causing some batch commands not run if return too fast: https://github.com/iced-rs/iced/issues/436
     tokio::time::sleep(Duration::from_millis(2)).await;
 
-    Arc::new(diff_mods)
+    Arc::new(diff)
 }
 
 async fn calculate_local_file_size(
     mut mods: Vec<Mod>,
-    workshop: Arc<Workshop>,
+    workshop: Arc<dyn WorkshopBackend>,
 ) -> Result<Arc<Vec<Mod>>, String> {
+    // steam only answers for the library arma is installed in, its manifests know about the others
+    let libraries = workshop.steam_libraries();
+    let manifest_items = steam_library::installed_items(&libraries);
+
     // loop through vectors and ask per mod
+    let mut unmeasured = vec![];
     for val in mods.iter_mut() {
-        match workshop.get_item_install_info(PublishedFileId(val.id)) {
+        match workshop
+            .install_info(val.id)
+            .or_else(|| manifest_items.get(&val.id).cloned())
+        {
+            // steam reports nothing for some installed mods, so measure those ourselves
+            Some(result) if result.size_on_disk == 0 && result.folder.is_dir() => {
+                unmeasured.push((val.id, result.folder));
+            }
             Some(result) => {
                 val.local_filesize = result.size_on_disk;
             }
             None => {
-                println!(
-                    "Could not find mod locally installed with id: {:?}",
-                    val.name
-                )
+                // the folder might still be there, even if steam does not know about it
+                let folder_name = val.id.to_string();
+                let folder = libraries
+                    .iter()
+                    .map(|library| steam_library::workshop_content_dir(library).join(&folder_name))
+                    .find(|folder| folder.is_dir());
+                match folder {
+                    Some(folder) => unmeasured.push((val.id, folder)),
+                    None => println!(
+                        "Could not find mod locally installed with id: {:?}",
+                        val.name
+                    ),
+                }
             }
         }
     }
+
+    let measured = disk::measure_dirs(unmeasured, MEASURE_CONCURRENCY).await;
+    for val in mods.iter_mut() {
+        if let Some(size) = measured.get(&val.id) {
+            val.local_filesize = *size;
+        }
+    }
     // sleep we need due to bug on windows causing some batch commands not run if return too fast: https://github.com/iced-rs/iced/issues/436
     tokio::time::sleep(Duration::from_millis(2)).await;
 
     Ok(Arc::new(mods))
 }
 
+/// Measures the folder of every installed mod, and returns the mods where steam reports a size far
+/// off what is on disk. That points at incomplete or corrupted downloads
+async fn verify_install_sizes(
+    mods: Vec<Mod>,
+    workshop: Arc<dyn WorkshopBackend>,
+) -> Arc<HashMap<u64, (u64, u64)>> {
+    // without a reported size there is nothing to compare against
+    let installed: Vec<_> = mods
+        .iter()
+        .filter_map(|item| Some((item.id, workshop.install_info(item.id)?)))
+        .filter(|(_, info)| info.size_on_disk > 0)
+        .collect();
+    let measured = disk::measure_dirs(
+        installed
+            .iter()
+            .map(|(id, info)| (*id, info.folder.clone()))
+            .collect(),
+        MEASURE_CONCURRENCY,
+    )
+    .await;
+
+    let mismatches = installed
+        .into_iter()
+        .filter_map(|(id, info)| {
+            let measured = match measured.get(&id) {
+                Some(size) => *size,
+                // steam thinks it is installed, but the folder is gone
+                None if !info.folder.exists() => 0,
+                None => return None,
+            };
+            disk::sizes_mismatch(info.size_on_disk, measured)
+                .then_some((id, (info.size_on_disk, measured)))
+        })
+        .collect();
+    Arc::new(mismatches)
+}
+
+/// Warning for mods whose install does not match the size steam reports
+fn size_warning(mismatches: &HashMap<u64, (u64, u64)>, id: u64) -> Option<String> {
+    mismatches.get(&id).map(|(reported, measured)| {
+        format!(
+            "Steam reports {}, but the folder holds {}. The download might be broken",
+            format_size(*reported, DECIMAL),
+            format_size(*measured, DECIMAL)
+        )
+    })
+}
+
 async fn unsub_selected_mods(
     mods: Vec<ModRow>,
-    workshop: Arc<Workshop>,
+    workshop: Arc<dyn WorkshopBackend>,
     progress: Arc<AtomicU32>,
 ) -> Result<(), String> {
+    let mut failed = vec![];
     for val in mods.iter().filter(|item| item.selected) {
         // for every loop we add one to progress to show what mod we are currently unsubbing
         progress.fetch_add(1, Ordering::Relaxed);
 
-        // await unsub
-        if workshop.unsub_from_mod(PublishedFileId(val.id)).await.is_ok() {}
+        // offline this removes the folder, which can fail as well
+        if let Err(e) = workshop.unsubscribe(val.id).await {
+            failed.push(format!("{}: {}", val.name, e));
+        }
+    }
+    match failed.is_empty() {
+        true => Ok(()),
+        false => Err(format!("Failed to unsubscribe from {}", failed.join(", "))),
+    }
+}
+
+async fn plan_sync(
+    keep_sets: Vec<ModPreset>,
+    all_mods: Vec<Mod>,
+    mut preset_mods: Vec<Mod>,
+    workshop: Arc<dyn WorkshopBackend>,
+) -> Result<Arc<SyncPlan>, WorkshopError> {
+    let mut asked = HashSet::new();
+    let mut download_sizes = HashMap::new();
+    loop {
+        let diff = diff::diff_mods(&keep_sets, &all_mods, &preset_mods);
+
+        // mods we are not subscribed to are not installed, so their size has to come from the
+        // workshop. Their dependencies can add more missing mods, so ask until nothing new shows up
+        let missing: Vec<u64> = diff
+            .not_subscribed
+            .iter()
+            .map(|missing| missing.item.id)
+            .filter(|id| asked.insert(*id))
+            .collect();
+        if missing.is_empty() {
+            return Ok(Arc::new(SyncPlan::new(&diff, &download_sizes)));
+        }
+
+        let items =
+            query_items_paged(&workshop, missing, QUERY_CONCURRENCY, &AtomicU32::new(0)).await?;
+        for item in items {
+            download_sizes.insert(item.id, item.details.file_size);
+            preset_mods.push(Mod {
+                dependencies: item.children,
+                ..Mod::from_workshop_id(item.id, Some(item.title))
+            });
+        }
+    }
+}
+
+/// Runs both halves of a sync plan, unsubscribing first so steam can start freeing space
+async fn sync_mods(
+    plan: Arc<SyncPlan>,
+    workshop: Arc<dyn WorkshopBackend>,
+    progress: Arc<AtomicU32>,
+) -> Result<(), String> {
+    let mut failed = vec![];
+    for val in plan.unsubscribe.iter() {
+        progress.fetch_add(1, Ordering::Relaxed);
+        if let Err(e) = workshop.unsubscribe(val.id).await {
+            failed.push(format!("unsubscribing {}: {}", val.name, e));
+        }
+    }
+    for val in plan.subscribe.iter() {
+        progress.fetch_add(1, Ordering::Relaxed);
+        if let Err(e) = workshop.subscribe(val.id).await {
+            failed.push(format!("subscribing {}: {}", val.name, e));
+        }
+    }
+    match failed.is_empty() {
+        true => Ok(()),
+        false => Err(format!("Failed {}", failed.join(", "))),
+    }
+}
+
+async fn sub_selected_mods(
+    mods: Vec<ModRow>,
+    workshop: Arc<dyn WorkshopBackend>,
+    progress: Arc<AtomicU32>,
+) -> Result<(), String> {
+    let mut failed = vec![];
+    for val in mods.iter().filter(|item| item.selected) {
+        // for every loop we add one to progress to show what mod we are currently subscribing to
+        progress.fetch_add(1, Ordering::Relaxed);
+
+        // steam starts downloading the mod on its own once subscribed
+        if let Err(e) = workshop.subscribe(val.id).await {
+            failed.push(format!("{}: {}", val.name, e));
+        }
+    }
+    match failed.is_empty() {
+        true => Ok(()),
+        false => Err(format!("Failed to subscribe to {}", failed.join(", "))),
     }
-    Ok(())
 }
 
 pub fn main() -> iced::Result {
-    iced::application("AMDU", Amdu::update, Amdu::view)
+    let launch = LaunchMode::from_args(std::env::args().skip(1));
+    let title = match launch {
+        Ok(LaunchMode::Demo(_)) => "AMDU (Demo)",
+        Ok(LaunchMode::Offline(_)) => "AMDU (Offline)",
+        _ => "AMDU",
+    };
+
+    iced::application(title, Amdu::update, Amdu::view)
         .subscription(Amdu::subscription)
         .theme(Amdu::theme)
         .window(
@@ -627,5 +2046,345 @@ pub fn main() -> iced::Result {
                 icon: Some(icon::from_file_data(include_bytes!("../gfx/icon.png"), None).expect("Failed to load icon")),
                 ..Default::default()
             })
-        .run_with(Amdu::new)
+        .run_with(move || Amdu::new(launch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CBA: u64 = 450814997;
+    const ACE: u64 = 463939057;
+    const ACRE: u64 = 751965892;
+    const RHS: u64 = 843425103;
+
+    /// ace needs cba, acre is in no preset and rhs is known to the workshop but not subscribed
+    const FIXTURE: &str = r#"{
+        "authors": {"76561197970370117": "CBA Team"},
+        "items": [
+            {"id": 450814997, "title": "CBA_A3", "tags": ["Mod"], "subscribed": true, "owner": 76561197970370117},
+            {"id": 463939057, "title": "ace", "tags": ["Mod"], "subscribed": true, "children": [450814997]},
+            {"id": 751965892, "title": "ACRE2", "tags": ["Mod"], "subscribed": true},
+            {"id": 843425103, "title": "RHS: AFRF", "tags": ["Mod"], "file_size": 1000}
+        ]
+    }"#;
+
+    fn workshop() -> Arc<dyn WorkshopBackend> {
+        Arc::new(FakeWorkshop::from_json(FIXTURE).unwrap())
+    }
+
+    /// Preset that only knows the ids, like an id list or a csv file
+    fn preset(ids: &[u64]) -> ModPreset {
+        let mods = ids
+            .iter()
+            .map(|id| Mod::from_workshop_id(*id, None))
+            .collect();
+        ModPreset::from_mods("Ops".to_string(), mods)
+    }
+
+    fn row(id: u64, selected: bool) -> ModRow {
+        ModRow::new(id, id.to_string(), String::new(), 0, selected)
+    }
+
+    fn row_ids(rows: &[ModRow]) -> Vec<u64> {
+        rows.iter().map(|row| row.id).collect()
+    }
+
+    async fn subscribed_mods(workshop: &Arc<dyn WorkshopBackend>) -> Vec<Mod> {
+        let progress = Arc::new(AtomicU32::new(0));
+        load_subscribed_mods(workshop.clone(), progress)
+            .await
+            .unwrap()
+            .to_vec()
+    }
+
+    #[tokio::test]
+    async fn diff_of_fake_subscriptions() {
+        let mods = subscribed_mods(&workshop()).await;
+        let diff = diff::diff_mods(&[preset(&[ACE, RHS])], &mods, &[]);
+
+        let ids = |mods: &[diff::PresetMod]| -> Vec<u64> {
+            mods.iter().map(|kept| kept.item.id).collect()
+        };
+        assert_eq!(
+            diff.remove.iter().map(|item| item.id).collect::<Vec<_>>(),
+            vec![ACRE]
+        );
+        // sorted by name, not by id
+        assert_eq!(ids(&diff.kept), vec![ACE, CBA]);
+        assert_eq!(diff.kept[1].required_by, vec!["ace".to_string()]);
+        assert_eq!(ids(&diff.not_subscribed), vec![RHS]);
+    }
+
+    #[tokio::test]
+    async fn unsub_only_touches_selected_rows() {
+        let workshop = workshop();
+        let progress = Arc::new(AtomicU32::new(0));
+        let rows = vec![row(ACRE, true), row(ACE, false)];
+
+        unsub_selected_mods(rows, workshop.clone(), progress.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(workshop.subscribed_items(), vec![CBA, ACE]);
+        assert_eq!(progress.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn unsub_reports_every_failed_item() {
+        let workshop = workshop();
+        let progress = Arc::new(AtomicU32::new(0));
+        let rows = vec![row(1, true), row(ACRE, true), row(2, true)];
+
+        let result = unsub_selected_mods(rows, workshop.clone(), progress.clone()).await;
+
+        let error = result.unwrap_err();
+        assert!(error.contains("1: ") && error.contains("2: "), "{}", error);
+        // the items after a failure are still unsubscribed
+        assert_eq!(workshop.subscribed_items(), vec![CBA, ACE]);
+        assert_eq!(progress.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn sub_reports_items_the_workshop_does_not_know() {
+        let workshop = workshop();
+        let progress = Arc::new(AtomicU32::new(0));
+        let rows = vec![row(RHS, true), row(1, true), row(2, false)];
+
+        let result = sub_selected_mods(rows, workshop.clone(), progress.clone()).await;
+
+        let error = result.unwrap_err();
+        assert!(error.contains('1'), "{}", error);
+        assert!(!error.contains('2'), "{}", error);
+        assert!(workshop.subscribed_items().contains(&RHS));
+        assert_eq!(progress.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn sync_makes_subscriptions_match_presets() {
+        let workshop = workshop();
+        let mods = subscribed_mods(&workshop).await;
+        let presets = vec![preset(&[ACE, RHS])];
+
+        let plan = plan_sync(presets, mods, vec![], workshop.clone())
+            .await
+            .unwrap();
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan.download_size, 1000);
+
+        let progress = Arc::new(AtomicU32::new(0));
+        sync_mods(plan, workshop.clone(), progress.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(workshop.subscribed_items(), vec![CBA, ACE, RHS]);
+        assert_eq!(progress.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn sync_keeps_dependencies_of_mods_to_subscribe() {
+        let workshop = workshop();
+        workshop.unsubscribe(ACE).await.unwrap();
+        let mods = subscribed_mods(&workshop).await;
+
+        // the preset leaves out cba, only ace knows it needs it
+        let plan = plan_sync(vec![preset(&[ACE])], mods, vec![], workshop.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            plan.unsubscribe
+                .iter()
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            vec![ACRE]
+        );
+        assert_eq!(
+            plan.subscribe
+                .iter()
+                .map(|item| item.id)
+                .collect::<Vec<_>>(),
+            vec![ACE]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_fills_lists_from_fetched_mods() {
+        let workshop = workshop();
+        let (mut amdu, _) = Amdu::new(Err("no steam in tests".to_string()));
+        amdu.workshop = Some(workshop.clone());
+        amdu.parser
+            .add_modpresets(
+                vec![(PresetSource::File("ops.txt".into()), preset(&[ACE, RHS]))],
+                true,
+            )
+            .unwrap();
+
+        let mods = subscribed_mods(&workshop).await;
+        let _ = amdu.update(Message::SubscribedModsFetched(Ok(Arc::new(mods))));
+
+        // the preset only had ids, the names come from the subscribed mods
+        let preset_names: Vec<String> = amdu.parser.get_modpresets()[0]
+            .mods
+            .iter()
+            .map(|item| item.name.clone())
+            .collect();
+        assert!(preset_names.contains(&"ace".to_string()));
+
+        // the task update returned, run by hand
+        let diff = calculate_diff_mods(
+            amdu.parser.get_modpresets(),
+            amdu.workshop_subbed_mods.clone(),
+            amdu.workshop_preset_mods.clone(),
+        )
+        .await;
+        let _ = amdu.update(Message::UpdateSelectionView(diff));
+
+        assert_eq!(row_ids(&amdu.mod_selection_list), vec![ACRE]);
+        assert!(amdu.mod_selection_list.iter().all(|row| row.selected));
+        assert_eq!(row_ids(&amdu.kept_list), vec![ACE, CBA]);
+        assert_eq!(amdu.kept_list[0].name, "ace");
+        assert!(amdu.kept_list[1].note.is_some());
+        assert_eq!(row_ids(&amdu.not_subscribed_list), vec![RHS]);
+    }
+
+    #[tokio::test]
+    async fn author_column_names_known_authors() {
+        let workshop = workshop();
+        let (mut amdu, _) = Amdu::new(Err("no steam in tests".to_string()));
+        amdu.workshop = Some(workshop.clone());
+        let mods = subscribed_mods(&workshop).await;
+        let diff = calculate_diff_mods(vec![], mods, vec![]).await;
+        let _ = amdu.update(Message::UpdateSelectionView(diff));
+
+        // nothing is looked up while the column is hidden
+        assert!(amdu
+            .mod_selection_list
+            .iter()
+            .all(|row| row.author.is_none()));
+
+        let _ = amdu.update(Message::ToggleColumn(Column::Author, true));
+        let authors: Vec<_> = amdu
+            .mod_selection_list
+            .iter()
+            .map(|row| (row.id, row.author.as_deref()))
+            .collect();
+        assert!(authors.contains(&(CBA, Some("CBA Team"))));
+        assert!(authors.contains(&(ACRE, None)));
+    }
+
+    #[tokio::test]
+    async fn offline_mod_sizes_are_measured() {
+        let dir = std::env::temp_dir().join(format!("amdu-offline-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("1234")).unwrap();
+        std::fs::write(dir.join("1234").join("mod.pbo"), vec![0; 2000]).unwrap();
+        let workshop: Arc<dyn WorkshopBackend> = Arc::new(OfflineWorkshop::scan(&dir).unwrap());
+
+        let mods = subscribed_mods(&workshop).await;
+        let mods = calculate_local_file_size(mods, workshop.clone())
+            .await
+            .unwrap();
+        let mismatches = verify_install_sizes(mods.to_vec(), workshop).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(mods[0].local_filesize, 2000);
+        // offline there is no reported size to be off from
+        assert!(mismatches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn offline_removal_can_skip_the_trash() {
+        let dir = std::env::temp_dir().join(format!("amdu-permanent-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("1234")).unwrap();
+        let workshop: Arc<dyn WorkshopBackend> = Arc::new(OfflineWorkshop::scan(&dir).unwrap());
+        let (mut amdu, _) = Amdu::new(Err("no steam in tests".to_string()));
+        amdu.workshop = Some(workshop.clone());
+
+        let _ = amdu.update(Message::ToggleDeletePermanently(true));
+        workshop.unsubscribe(1234).await.unwrap();
+        let removed = !dir.join("1234").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(removed);
+        assert!(workshop.subscribed_items().is_empty());
+    }
+
+    #[tokio::test]
+    async fn verified_sizes_keep_the_selection() {
+        let workshop = workshop();
+        let (mut amdu, _) = Amdu::new(Err("no steam in tests".to_string()));
+        amdu.workshop = Some(workshop.clone());
+        let mods = subscribed_mods(&workshop).await;
+        let diff = calculate_diff_mods(vec![], mods, vec![]).await;
+        let _ = amdu.update(Message::UpdateSelectionView(diff));
+        amdu.mod_selection_list
+            .iter_mut()
+            .for_each(|row| row.selected = row.id == ACRE);
+
+        let mismatches = HashMap::from([(CBA, (1000, 10))]);
+        let _ = amdu.update(Message::SizesVerified(Arc::new(mismatches)));
+
+        let by_id = |id: u64| {
+            amdu.mod_selection_list
+                .iter()
+                .find(|row| row.id == id)
+                .unwrap()
+        };
+        assert!(by_id(CBA).warning.is_some());
+        assert!(!by_id(CBA).selected);
+        assert!(by_id(ACRE).selected);
+        assert!(by_id(ACRE).warning.is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_batch_is_kept_until_the_next_one() {
+        let (mut amdu, _) = Amdu::new(Err("no steam in tests".to_string()));
+        amdu.workshop = Some(workshop());
+
+        let _ = amdu.update(Message::SyncPlanned(Err(WorkshopError::Offline)));
+        assert!(amdu.action_error.is_some());
+
+        let _ = amdu.update(Message::BatchFinished(Err("Failed deleting".to_string())));
+        assert_eq!(amdu.action_error.as_deref(), Some("Failed deleting"));
+
+        let _ = amdu.update(Message::SubscribeSelected);
+        assert!(amdu.action_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_preset_query_is_asked_again() {
+        let (mut amdu, _) = Amdu::new(Err("no steam in tests".to_string()));
+        amdu.workshop = Some(workshop());
+        amdu.parser
+            .add_modpresets(
+                vec![(PresetSource::File("ops.txt".into()), preset(&[RHS]))],
+                true,
+            )
+            .unwrap();
+        let _ = amdu.presets_changed();
+        assert!(amdu.preset_queries.contains(&RHS));
+
+        let failed = Message::PresetModsFetched(vec![RHS], Err(WorkshopError::Disconnected));
+        let _ = amdu.update(failed);
+        assert!(!amdu.preset_queries.contains(&RHS));
+
+        let _ = amdu.presets_changed();
+        assert!(amdu.preset_queries.contains(&RHS));
+    }
+
+    #[tokio::test]
+    async fn failed_fetch_is_kept_until_reloaded() {
+        let (mut amdu, _) = Amdu::new(Err("no steam in tests".to_string()));
+        amdu.workshop = Some(workshop());
+
+        let failed = Message::SubscribedModsFetched(Err(WorkshopError::Disconnected));
+        let _ = amdu.update(failed);
+        assert!(amdu.fetch_error.is_some());
+
+        let _ = amdu.update(Message::ReloadSubscribed);
+        assert!(amdu.loading_page_count.is_some());
+
+        let mods = subscribed_mods(&workshop()).await;
+        let _ = amdu.update(Message::SubscribedModsFetched(Ok(Arc::new(mods))));
+        assert!(amdu.fetch_error.is_none());
+    }
 }
@@ -0,0 +1,139 @@
+#![deny(clippy::all)]
+
+use std::path::Path;
+
+/// Info from the `meta.cpp` the launcher/steam writes into every workshop mod folder,
+/// or the `mod.cpp` mod authors ship with their mod
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModMeta {
+    pub published_id: Option<u64>,
+    pub name: Option<String>,
+    pub timestamp: Option<u64>,
+}
+
+impl ModMeta {
+    /// Parses the `key = value;` lines of a meta.cpp/mod.cpp, unknown keys are ignored
+    pub fn parse(raw_contents: &str) -> Self {
+        let mut meta = ModMeta::default();
+
+        for line in raw_contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_end_matches(';').trim().trim_matches('"');
+
+            match key.trim().to_lowercase().as_str() {
+                // steam writes 0 for mods that aren't from the workshop
+                "publishedid" => meta.published_id = value.parse().ok().filter(|id| *id != 0),
+                "name" if !value.is_empty() => meta.name = Some(value.to_string()),
+                "timestamp" => meta.timestamp = value.parse().ok(),
+                _ => {}
+            }
+        }
+        meta
+    }
+
+    /// Reads meta.cpp from a mod folder, filling in anything missing from mod.cpp
+    pub async fn read_from_dir(dir: &Path) -> Option<Self> {
+        let meta = tokio::fs::read_to_string(dir.join("meta.cpp"))
+            .await
+            .ok()
+            .map(|raw| ModMeta::parse(&raw));
+        let mod_cpp = tokio::fs::read_to_string(dir.join("mod.cpp"))
+            .await
+            .ok()
+            .map(|raw| ModMeta::parse(&raw));
+        ModMeta::merge(meta, mod_cpp)
+    }
+
+    /// Same as `read_from_dir`, for callers outside the async runtime
+    pub fn read_from_dir_blocking(dir: &Path) -> Option<Self> {
+        let meta = std::fs::read_to_string(dir.join("meta.cpp"))
+            .ok()
+            .map(|raw| ModMeta::parse(&raw));
+        let mod_cpp = std::fs::read_to_string(dir.join("mod.cpp"))
+            .ok()
+            .map(|raw| ModMeta::parse(&raw));
+        ModMeta::merge(meta, mod_cpp)
+    }
+
+    /// Unix time of the timestamp steam writes, which is a .NET `DateTime.ToBinary()`:
+    /// 100ns ticks since year 1 with the kind of time in the two top bits
+    pub fn timestamp_unix(&self) -> Option<u32> {
+        const TICKS_PER_SECOND: u64 = 10_000_000;
+        const UNIX_EPOCH_SECONDS: u64 = 62_135_596_800;
+
+        let ticks = self.timestamp? & 0x3FFF_FFFF_FFFF_FFFF;
+        (ticks / TICKS_PER_SECOND)
+            .checked_sub(UNIX_EPOCH_SECONDS)
+            .and_then(|secs| u32::try_from(secs).ok())
+    }
+
+    fn merge(meta: Option<Self>, mod_cpp: Option<Self>) -> Option<Self> {
+        match (meta, mod_cpp) {
+            (Some(mut meta), Some(mod_cpp)) => {
+                meta.name = meta.name.or(mod_cpp.name);
+                meta.published_id = meta.published_id.or(mod_cpp.published_id);
+                Some(meta)
+            }
+            (meta, mod_cpp) => meta.or(mod_cpp),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_value_lines() {
+        let meta = ModMeta::parse(
+            "protocol = 1;\npublishedid = 450814997;\nname = \"CBA_A3\";\ntimestamp = 5249597386152423734;\n",
+        );
+        assert_eq!(
+            meta,
+            ModMeta {
+                published_id: Some(450814997),
+                name: Some("CBA_A3".to_string()),
+                timestamp: Some(5249597386152423734),
+            }
+        );
+    }
+
+    #[test]
+    fn published_id_zero_is_no_id() {
+        let meta = ModMeta::parse("publishedid = 0;\nname = \"\";");
+        assert_eq!(meta, ModMeta::default());
+    }
+
+    #[test]
+    fn mod_cpp_fills_in_what_meta_cpp_lacks() {
+        let meta = ModMeta::parse("publishedid = 463939057;\ntimestamp = 1;");
+        let mod_cpp = ModMeta::parse("name = \"ace\";\npublishedid = 1;");
+
+        let merged = ModMeta::merge(Some(meta), Some(mod_cpp.clone())).unwrap();
+        assert_eq!(merged.published_id, Some(463939057));
+        assert_eq!(merged.name.as_deref(), Some("ace"));
+        assert_eq!(merged.timestamp, Some(1));
+        assert_eq!(ModMeta::merge(None, Some(mod_cpp.clone())), Some(mod_cpp));
+        assert_eq!(ModMeta::merge(None, None), None);
+    }
+
+    #[test]
+    fn timestamp_is_converted_from_dotnet_ticks() {
+        let meta = ModMeta {
+            timestamp: Some(5249597386152423734),
+            ..Default::default()
+        };
+        // 2022-06-18 08:12:52 utc, the top bits only mark the kind of time
+        assert_eq!(meta.timestamp_unix(), Some(1655539972));
+
+        // before 1970 does not fit
+        let meta = ModMeta {
+            timestamp: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(meta.timestamp_unix(), None);
+        assert_eq!(ModMeta::default().timestamp_unix(), None);
+    }
+}
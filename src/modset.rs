@@ -0,0 +1,220 @@
+#![deny(clippy::all)]
+
+use std::collections::btree_map;
+use std::collections::BTreeMap;
+use steamworks::PublishedFileId;
+
+use crate::presets::Mod;
+
+/// Set of mods where identity is the workshop id only, names and other data don't matter for membership.
+/// When the same id is added twice, the first mod is kept.
+#[derive(Debug, Clone, Default)]
+pub struct ModSet {
+    mods: BTreeMap<PublishedFileId, Mod>,
+}
+
+impl ModSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns false if a mod with the same id was already in the set
+    pub fn insert(&mut self, item: Mod) -> bool {
+        match self.mods.entry(PublishedFileId(item.id)) {
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(item);
+                true
+            }
+            btree_map::Entry::Occupied(_) => false,
+        }
+    }
+
+    pub fn remove(&mut self, id: PublishedFileId) -> Option<Mod> {
+        self.mods.remove(&id)
+    }
+
+    pub fn contains(&self, id: PublishedFileId) -> bool {
+        self.mods.contains_key(&id)
+    }
+
+    pub fn get(&self, id: PublishedFileId) -> Option<&Mod> {
+        self.mods.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.mods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mods.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Mod> {
+        self.mods.values()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = PublishedFileId> + '_ {
+        self.mods.keys().copied()
+    }
+
+    /// Mods in either set, mods from `self` win when both have the id
+    pub fn union(&self, other: &ModSet) -> ModSet {
+        let mut result = self.clone();
+        result.extend(other.iter().cloned());
+        result
+    }
+
+    /// Mods of `self` whose id is also in `other`
+    pub fn intersection(&self, other: &ModSet) -> ModSet {
+        self.iter()
+            .filter(|item| other.contains(PublishedFileId(item.id)))
+            .cloned()
+            .collect()
+    }
+
+    /// Mods of `self` whose id is not in `other`
+    pub fn difference(&self, other: &ModSet) -> ModSet {
+        self.iter()
+            .filter(|item| !other.contains(PublishedFileId(item.id)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn into_vec(self) -> Vec<Mod> {
+        self.mods.into_values().collect()
+    }
+}
+
+impl FromIterator<Mod> for ModSet {
+    fn from_iter<T: IntoIterator<Item = Mod>>(iter: T) -> Self {
+        let mut set = ModSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Mod> for ModSet {
+    fn extend<T: IntoIterator<Item = Mod>>(&mut self, iter: T) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl IntoIterator for ModSet {
+    type Item = Mod;
+    type IntoIter = btree_map::IntoValues<PublishedFileId, Mod>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.mods.into_values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn named(id: u64, name: &str) -> Mod {
+        Mod::from_workshop_id(id, Some(name.to_string()))
+    }
+
+    fn ids(set: &ModSet) -> BTreeSet<u64> {
+        set.ids().map(|id| id.0).collect()
+    }
+
+    /// Small deterministic generator, so the property checks need no extra crates
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        /// Mods with ids from a small range, so sets overlap, and names that often disagree on the same id
+        fn mods(&mut self) -> Vec<Mod> {
+            let count = self.next() % 12;
+            (0..count)
+                .map(|_| {
+                    let id = self.next() % 16;
+                    named(id, &format!("mod {}", self.next() % 4))
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn same_id_with_other_name_is_one_entry() {
+        let mut set = ModSet::new();
+        assert!(set.insert(named(450814997, "CBA_A3")));
+        assert!(!set.insert(named(450814997, "Community Base Addons")));
+
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.get(PublishedFileId(450814997)).unwrap().name, "CBA_A3");
+    }
+
+    #[test]
+    fn same_name_with_other_ids_stays_separate() {
+        let set: ModSet = vec![named(1, "ace"), named(2, "ace")].into_iter().collect();
+
+        assert_eq!(set.len(), 2);
+        assert_eq!(ids(&set), BTreeSet::from([1, 2]));
+    }
+
+    #[test]
+    fn set_operations_go_by_id_only() {
+        let left: ModSet = vec![named(1, "a"), named(2, "b"), named(3, "c")]
+            .into_iter()
+            .collect();
+        let right: ModSet = vec![named(2, "other b"), named(3, "c"), named(4, "d")]
+            .into_iter()
+            .collect();
+
+        assert_eq!(ids(&left.union(&right)), BTreeSet::from([1, 2, 3, 4]));
+        assert_eq!(ids(&left.intersection(&right)), BTreeSet::from([2, 3]));
+        assert_eq!(ids(&left.difference(&right)), BTreeSet::from([1]));
+        // the mods of the left side are kept, whatever the right side calls them
+        assert_eq!(
+            left.intersection(&right)
+                .get(PublishedFileId(2))
+                .unwrap()
+                .name,
+            "b"
+        );
+    }
+
+    #[test]
+    fn set_operations_match_id_sets() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let left_mods = rng.mods();
+            let right_mods = rng.mods();
+            let left_ids: BTreeSet<u64> = left_mods.iter().map(|item| item.id).collect();
+            let right_ids: BTreeSet<u64> = right_mods.iter().map(|item| item.id).collect();
+            let left: ModSet = left_mods.iter().cloned().collect();
+            let right: ModSet = right_mods.iter().cloned().collect();
+
+            assert_eq!(ids(&left), left_ids);
+            assert_eq!(left.len(), left_ids.len());
+            assert_eq!(
+                ids(&left.union(&right)),
+                &left_ids | &right_ids,
+                "union of {:?} and {:?}",
+                left_ids,
+                right_ids
+            );
+            assert_eq!(ids(&left.intersection(&right)), &left_ids & &right_ids);
+            assert_eq!(ids(&left.difference(&right)), &left_ids - &right_ids);
+
+            // the first mod with an id is the one kept
+            for item in left.iter() {
+                let first = left_mods.iter().find(|m| m.id == item.id).unwrap();
+                assert_eq!(item.name, first.name);
+            }
+        }
+    }
+}
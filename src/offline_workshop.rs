@@ -0,0 +1,145 @@
+#![deny(clippy::all)]
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::backend::{BoxFuture, InstallInfo, WorkshopBackend, WorkshopError, WorkshopItem};
+use crate::modmeta::ModMeta;
+use crate::presets::{Mod, WorkshopDetails};
+
+/// Mod folder found by the scan
+#[derive(Debug, Clone)]
+struct OfflineMod {
+    folder: PathBuf,
+    name: String,
+    timestamp: u32,
+}
+
+/// Workshop read from the mod folders on disk, for servers and steamcmd installs without a steam
+/// client. The installed mods count as subscribed, and unsubscribing removes the folder
+pub struct OfflineWorkshop {
+    dir: PathBuf,
+    mods: Mutex<BTreeMap<u64, OfflineMod>>,
+    /// Folders go to the trash, unless asked otherwise
+    delete_permanently: AtomicBool,
+}
+
+impl OfflineWorkshop {
+    /// Scans the folders in `dir`, like a server folder with @mods or a workshop content folder
+    pub fn scan(dir: &Path) -> Result<Self, String> {
+        let entries =
+            std::fs::read_dir(dir).map_err(|e| format!("Failed reading {:?}: {}", dir, e))?;
+
+        let mut mods = BTreeMap::new();
+        for entry in entries.flatten() {
+            let folder = entry.path();
+            if !folder.is_dir() {
+                continue;
+            }
+            let folder_name = entry.file_name().to_string_lossy().to_string();
+            let meta = ModMeta::read_from_dir_blocking(&folder).unwrap_or_default();
+
+            // workshop content folders are named by id, and some mods ship without a meta.cpp
+            let Some(id) = meta.published_id.or(folder_name.parse().ok()) else {
+                println!("Skipping {:?}, it has no workshop id", folder);
+                continue;
+            };
+            mods.insert(
+                id,
+                OfflineMod {
+                    name: meta.name.clone().unwrap_or(folder_name),
+                    timestamp: meta.timestamp_unix().unwrap_or_default(),
+                    folder,
+                },
+            );
+        }
+
+        Ok(OfflineWorkshop {
+            dir: dir.to_path_buf(),
+            mods: Mutex::new(mods),
+            delete_permanently: AtomicBool::new(false),
+        })
+    }
+}
+
+impl WorkshopBackend for OfflineWorkshop {
+    fn subscribed_items(&self) -> Vec<u64> {
+        self.mods.lock().unwrap().keys().copied().collect()
+    }
+
+    fn query_items(
+        &self,
+        ids: Vec<u64>,
+    ) -> BoxFuture<'_, Result<Vec<WorkshopItem>, WorkshopError>> {
+        // without the workshop all we know is what the mod folder says about itself
+        let mods = self.mods.lock().unwrap();
+        let items = ids
+            .iter()
+            .filter_map(|id| {
+                let found = mods.get(id)?;
+                Some(WorkshopItem {
+                    id: *id,
+                    title: found.name.clone(),
+                    url: Mod::from_workshop_id(*id, None).url,
+                    details: WorkshopDetails {
+                        time_updated: found.timestamp,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+            })
+            .collect();
+        Box::pin(async move { Ok(items) })
+    }
+
+    fn install_info(&self, id: u64) -> Option<InstallInfo> {
+        // no size, the folders are measured like the ones steam reports no size for
+        let mods = self.mods.lock().unwrap();
+        let found = mods.get(&id)?;
+        Some(InstallInfo {
+            folder: found.folder.clone(),
+            size_on_disk: 0,
+            timestamp: found.timestamp,
+        })
+    }
+
+    fn subscribe(&self, _id: u64) -> BoxFuture<'_, Result<(), WorkshopError>> {
+        Box::pin(async move { Err(WorkshopError::Offline) })
+    }
+
+    fn unsubscribe(&self, id: u64) -> BoxFuture<'_, Result<(), WorkshopError>> {
+        let found = self.mods.lock().unwrap().get(&id).cloned();
+        let permanently = self.delete_permanently.load(Ordering::Relaxed);
+        Box::pin(async move {
+            let Some(found) = found else {
+                return Ok(());
+            };
+            tokio::task::spawn_blocking(move || match permanently {
+                true => std::fs::remove_dir_all(&found.folder).map_err(|e| e.to_string()),
+                false => trash::delete(&found.folder).map_err(|e| e.to_string()),
+            })
+            .await
+            .map_err(|_| WorkshopError::Disconnected)?
+            .map_err(WorkshopError::Io)?;
+            self.mods.lock().unwrap().remove(&id);
+            Ok(())
+        })
+    }
+
+    fn set_delete_permanently(&self, permanently: bool) {
+        self.delete_permanently
+            .store(permanently, Ordering::Relaxed);
+    }
+
+    fn is_dlc_installed(&self, _dlc_app_id: u32) -> bool {
+        // only steam knows, so don't warn about every dlc a preset needs
+        true
+    }
+
+    fn game_install_dir(&self) -> Option<PathBuf> {
+        // servers keep their @mods next to the server itself
+        Some(self.dir.clone())
+    }
+}
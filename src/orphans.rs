@@ -0,0 +1,107 @@
+#![deny(clippy::all)]
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use crate::backend::WorkshopBackend;
+use crate::disk::dir_size;
+use crate::steam_library;
+
+/// Folder in a workshop content directory for an item we are not subscribed to
+#[derive(Debug, Clone)]
+pub struct OrphanFolder {
+    pub id: u64,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Workshop content directories of the game, found through the installed subscribed items and the
+/// steam libraries
+pub fn workshop_content_dirs(workshop: &dyn WorkshopBackend) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = workshop
+        .subscribed_items()
+        .into_iter()
+        .filter_map(|id| workshop.install_info(id))
+        .filter_map(|info| info.folder.parent().map(Path::to_path_buf))
+        .collect();
+
+    dirs.extend(
+        workshop
+            .steam_libraries()
+            .iter()
+            .map(|library| steam_library::workshop_content_dir(library)),
+    );
+
+    dirs.retain(|dir| dir.is_dir());
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+/// Item folders in the workshop content directories whose id is not subscribed, with their size on disk
+pub async fn find_orphans(workshop: Arc<dyn WorkshopBackend>) -> Vec<OrphanFolder> {
+    // finding the libraries and walking folders of a few GB takes a while, keep it off the runtime
+    tokio::task::spawn_blocking(move || {
+        let subscribed: HashSet<u64> = workshop.subscribed_items().into_iter().collect();
+        let mut orphans = vec![];
+        for dir in workshop_content_dirs(workshop.as_ref()) {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                // only folders named like a workshop id are item folders
+                let Some(id) = entry
+                    .file_name()
+                    .to_str()
+                    .and_then(|name| name.parse().ok())
+                else {
+                    continue;
+                };
+                let path = entry.path();
+                if subscribed.contains(&id) || !path.is_dir() {
+                    continue;
+                }
+                match dir_size(&path) {
+                    Ok(size) => orphans.push(OrphanFolder { id, path, size }),
+                    Err(e) => println!("Failed measuring {:?}: {}", path, e),
+                }
+            }
+        }
+        orphans.sort_by_key(|orphan| std::cmp::Reverse(orphan.size));
+        orphans
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Deletes the folders, moving them to the trash unless `permanently` is set
+pub async fn delete_folders(
+    folders: Vec<PathBuf>,
+    permanently: bool,
+    progress: Arc<AtomicU32>,
+) -> Result<(), String> {
+    let mut failed = vec![];
+    for folder in folders {
+        // for every loop we add one to progress to show what folder we are currently deleting
+        progress.fetch_add(1, Ordering::Relaxed);
+
+        let path = folder.clone();
+        let result = tokio::task::spawn_blocking(move || match permanently {
+            true => std::fs::remove_dir_all(&path).map_err(|e| e.to_string()),
+            false => trash::delete(&path).map_err(|e| e.to_string()),
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result);
+
+        if let Err(e) = result {
+            failed.push(format!("{:?}: {}", folder, e));
+        }
+    }
+    match failed.is_empty() {
+        true => Ok(()),
+        false => Err(format!("Failed deleting {}", failed.join(", "))),
+    }
+}
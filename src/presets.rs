@@ -2,12 +2,19 @@
 
 use html_query_ast::parse_string;
 use html_query_extractor::extract;
-use regex::Regex;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::Deserialize;
 use std::cmp::Ordering;
 use std::ffi::OsStr;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::Utf8Error;
 use std::sync::Arc;
 
+use crate::modmeta::ModMeta;
+use crate::workshop_ref::{parse_workshop_ref, WorkshopRefError};
+
 #[derive(Debug, Clone)]
 pub struct Mod {
     pub tags: Vec<String>,
@@ -15,7 +22,53 @@ pub struct Mod {
     pub id: u64,
     pub name: String,
     pub local_filesize: u64,
+    /// What the workshop knows about the mod, only set for mods we queried
+    pub details: Option<WorkshopDetails>,
+    /// Workshop ids of the items the mod requires
+    pub dependencies: Vec<u64>,
+}
+
+/// Workshop metadata of an item beyond its name
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WorkshopDetails {
+    /// Steam id of the author
+    pub owner: u64,
+    /// Unix timestamps
+    pub time_created: u32,
+    pub time_updated: u32,
+    /// Size on the workshop, can differ a lot from the size on disk
+    pub file_size: u64,
+    /// Share of positive votes, 0 to 1
+    pub score: f32,
+    pub num_upvotes: u32,
+    pub num_downvotes: u32,
+    pub description: String,
+    pub preview_url: Option<String>,
+}
+impl Mod {
+    /// Mod only known by its workshop id, with the given name or the id as placeholder name until resolved
+    pub fn from_workshop_id(id: u64, name: Option<String>) -> Self {
+        Mod {
+            tags: vec![],
+            url: format!(
+                "https://steamcommunity.com/sharedfiles/filedetails/?id={}",
+                id
+            ),
+            id,
+            name: name.unwrap_or_else(|| id.to_string()),
+            local_filesize: 0,
+            details: None,
+            dependencies: vec![],
+        }
+    }
+
+    /// True while the mod only has its id as name
+    pub fn has_placeholder_name(&self) -> bool {
+        self.name.is_empty() || self.name == self.id.to_string()
+    }
 }
+
 impl PartialEq for Mod {
     fn eq(&self, other: &Self) -> bool {
         self.id == other.id
@@ -23,9 +76,10 @@ impl PartialEq for Mod {
 }
 impl Eq for Mod {}
 
+// ordered by id to agree with Eq, sort by name explicitly where it's shown
 impl Ord for Mod {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.name.cmp(&other.name)
+        self.id.cmp(&other.id)
     }
 }
 
@@ -35,35 +89,149 @@ impl PartialOrd for Mod {
     }
 }
 
+/// Creator DLC or other paid content a preset requires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dlc {
+    pub name: String,
+    pub app_id: Option<u32>,
+    pub url: String,
+}
+
+/// Mod that is loaded from a local @folder instead of the workshop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalMod {
+    pub name: String,
+    pub folder: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum PresetError {
+    Io(Arc<std::io::Error>),
+    NotLauncherPreset,
+    NoModTable,
+    BadModUrl(String),
+    Encoding(Utf8Error),
+    InvalidXml(String),
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PresetError::Io(e) => write!(f, "Could not read file: {}", e),
+            PresetError::NotLauncherPreset => write!(f, "Not an Arma 3 Launcher preset"),
+            PresetError::NoModTable => write!(f, "Preset contains no mod list"),
+            PresetError::BadModUrl(url) => write!(f, "Invalid workshop url: '{}'", url),
+            PresetError::Encoding(e) => write!(f, "File is not valid UTF-8: {}", e),
+            PresetError::InvalidXml(e) => write!(f, "Invalid preset xml: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+impl From<std::io::Error> for PresetError {
+    fn from(e: std::io::Error) -> Self {
+        PresetError::Io(Arc::new(e))
+    }
+}
+
+/// File formats we can read presets from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetFormat {
+    /// Exported preset from the launcher
+    Html,
+    /// The launchers own preset storage
+    Preset2,
+    /// One workshop id or url per line
+    IdList,
+    /// Spreadsheet export with an id or url column
+    Csv,
+    /// Startup parameter like `-mod=@ace;@cba_a3`
+    ModParam,
+}
+
+impl PresetFormat {
+    /// Picks the format by extension, text files are told apart by their contents
+    pub fn detect(path: &Path, raw_contents: &str) -> Self {
+        let extension = path
+            .extension()
+            .and_then(OsStr::to_str)
+            .unwrap_or("")
+            .to_lowercase();
+
+        match extension.as_str() {
+            "preset2" => PresetFormat::Preset2,
+            "csv" => PresetFormat::Csv,
+            "txt" | "" => {
+                let trimmed = raw_contents.trim_start();
+                if raw_contents.contains("-mod=") || trimmed.starts_with('@') {
+                    PresetFormat::ModParam
+                } else {
+                    PresetFormat::IdList
+                }
+            }
+            _ => PresetFormat::Html,
+        }
+    }
+}
+
+/// Result of loading a single preset file, so one broken file doesn't stop the others from loading
+#[derive(Debug, Clone)]
+pub struct PresetFileResult {
+    pub path: PathBuf,
+    pub result: Result<ModPreset, PresetError>,
+}
+
+/// Entry of a preset that was skipped because it doesn't point to a workshop item
+#[derive(Debug, Clone)]
+pub struct PresetWarning {
+    pub entry: String,
+    pub reason: WorkshopRefError,
+}
+
+impl fmt::Display for PresetWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Skipped '{}': {}", self.entry, self.reason)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ModPreset {
     pub name: String,
     pub mods: Vec<Mod>,
+    pub dlcs: Vec<Dlc>,
+    pub local_mods: Vec<LocalMod>,
+    pub warnings: Vec<PresetWarning>,
 }
 
 impl ModPreset {
-    pub fn new(raw_contents: String, filename: Option<&OsStr>) -> Result<Self, String> {
+    pub fn new(raw_contents: String, filename: Option<&OsStr>) -> Result<Self, PresetError> {
         // vector storing mod_ids
         let mut mods = Vec::new();
+        let mut local_mods = Vec::new();
+        let mut warnings = Vec::new();
 
         // parse raw contents
         let name_parse = parse_string("{name: strong}").expect("parse expression failed");
         let name_output = extract(raw_contents.as_str(), &name_parse);
 
-        // if name doens't exist, we use filename?
-        let name = match name_output["name"].as_str() {
-            Some(str) => str,
-            None => filename.unwrap().to_str().unwrap(),
+        // if name doens't exist, we use filename
+        let name = match (name_output["name"].as_str(), filename) {
+            (Some(str), _) if !str.is_empty() => str.to_string(),
+            (_, Some(file)) => file.to_string_lossy().to_string(),
+            _ => "Unnamed Preset".to_string(),
         };
 
         let parsed =
-            parse_string("{mods: [data-type=ModContainer]| [ {name: td, url: a | @(href)} ] }")
+            parse_string("{mods: [data-type=ModContainer]| [ {name: td, url: a | @(href), meta: [data-meta] | @(data-meta)} ], dlcs: [data-type=DlcContainer]| [ {name: td, url: a | @(href)} ] }")
                 .expect("parse expression failed");
         let output = extract(raw_contents.as_str(), &parsed);
-        let mods_list = output["mods"].as_array().unwrap();
-
-        //regex build
-        let re = Regex::new(r"(?<id>\d{4,})").unwrap();
+        let mods_list = match output["mods"].as_array() {
+            Some(list) => list,
+            // launcher presets always carry the arma meta tags, if those are missing it is some other html file
+            None if is_launcher_html(&raw_contents) => return Err(PresetError::NoModTable),
+            None => return Err(PresetError::NotLauncherPreset),
+        };
 
         // loop through items
         for val in mods_list {
@@ -71,20 +239,34 @@ impl ModPreset {
             let parsed_name = val["name"].as_str().unwrap_or("");
             let parsed_url = val["url"].as_str().unwrap_or("");
 
-            // test if name and url exists. If it does not exists it is likely local mods and we just skip them
-            if parsed_name.is_empty() || parsed_url.is_empty() {
+            // rows without a link are local mods, the launcher stores their folder in data-meta as "local:<folder>|..."
+            if parsed_url.is_empty() {
+                if parsed_name.is_empty() {
+                    continue;
+                }
+                let folder = val["meta"]
+                    .as_str()
+                    .and_then(|meta| meta.strip_prefix("local:"))
+                    .and_then(|meta| meta.split('|').next())
+                    .filter(|folder| !folder.is_empty())
+                    .unwrap_or(parsed_name);
+                local_mods.push(LocalMod {
+                    name: parsed_name.to_string(),
+                    folder: folder.to_string(),
+                });
                 continue;
             }
 
-            // regex to get id and parse it
-            let caps = re.find(parsed_url);
-
-            if caps.is_none() {
-                println!("current mod: '{}', has no url with id in it", parsed_url);
-                continue;
-            }
-
-            let id = caps.unwrap().as_str().parse::<u64>().unwrap();
+            let id = match parse_workshop_ref(parsed_url) {
+                Ok(id) => id,
+                Err(reason) => {
+                    warnings.push(PresetWarning {
+                        entry: format!("{} ({})", parsed_name, parsed_url),
+                        reason,
+                    });
+                    continue;
+                }
+            };
 
             // store in vector
             mods.push(Mod {
@@ -93,23 +275,579 @@ impl ModPreset {
                 id,
                 name: parsed_name.to_string(),
                 local_filesize: 0,
+                details: None,
+                dependencies: vec![],
+            });
+        }
+
+        // dlcs are optional, older presets don't have the table at all
+        let dlcs = output["dlcs"]
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|val| {
+                        let name = val["name"].as_str().filter(|name| !name.is_empty())?;
+                        let url = val["url"].as_str().unwrap_or("");
+                        Some(Dlc {
+                            name: name.to_string(),
+                            app_id: parse_store_app_id(url),
+                            url: url.to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(ModPreset {
+            name,
+            mods,
+            dlcs,
+            local_mods,
+            warnings,
+        })
+    }
+
+    /// Parses the launchers own `.preset2` xml files. These only store ids, so mod names are the ids until resolved
+    pub fn from_preset2(raw_contents: &str, filename: Option<&OsStr>) -> Result<Self, PresetError> {
+        let name = name_from_filename(filename);
+
+        let mut reader = Reader::from_str(raw_contents);
+        reader.config_mut().trim_text(true);
+
+        // path of elements we are currently in, so we know which list an <id> belongs to
+        let mut path: Vec<String> = vec![];
+        let mut has_root = false;
+        let mut has_mod_list = false;
+        let mut mods = vec![];
+        let mut local_mods = vec![];
+        let mut dlcs = vec![];
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if path.is_empty() => {
+                    if e.name().as_ref() != b"addons-presets" {
+                        return Err(PresetError::NotLauncherPreset);
+                    }
+                    has_root = true;
+                    path.push("addons-presets".to_string());
+                }
+                Ok(Event::Start(e)) => {
+                    let tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    if tag == "published-ids" {
+                        has_mod_list = true;
+                    }
+                    path.push(tag);
+                }
+                Ok(Event::Empty(e)) if e.name().as_ref() == b"published-ids" => {
+                    has_mod_list = true;
+                }
+                Ok(Event::End(_)) => {
+                    path.pop();
+                }
+                Ok(Event::Text(e)) => {
+                    let value = e
+                        .unescape()
+                        .map_err(|e| PresetError::InvalidXml(e.to_string()))?;
+                    let parent = path.len().checked_sub(2).map(|i| path[i].as_str());
+
+                    match (path.last().map(String::as_str), parent) {
+                        (Some("id"), Some("published-ids")) => {
+                            if let Some(folder) = value.strip_prefix("local:") {
+                                let folder_name = folder
+                                    .split(['\\', '/'])
+                                    .rfind(|part| !part.is_empty())
+                                    .unwrap_or(folder);
+                                local_mods.push(LocalMod {
+                                    name: folder_name.to_string(),
+                                    folder: folder.to_string(),
+                                });
+                            } else {
+                                let raw_id = value.strip_prefix("steam:").unwrap_or(&value);
+                                let id = raw_id
+                                    .parse::<u64>()
+                                    .map_err(|_| PresetError::BadModUrl(value.to_string()))?;
+                                mods.push(Mod::from_workshop_id(id, None));
+                            }
+                        }
+                        (Some("id"), Some("dlcs-appids")) => {
+                            let app_id = value.parse::<u32>().ok();
+                            dlcs.push(Dlc {
+                                name: format!("DLC {}", value),
+                                app_id,
+                                url: format!("https://store.steampowered.com/app/{}", value),
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(PresetError::InvalidXml(e.to_string())),
+                _ => {}
+            }
+        }
+
+        if !has_root {
+            return Err(PresetError::NotLauncherPreset);
+        }
+        if !has_mod_list {
+            return Err(PresetError::NoModTable);
+        }
+
+        Ok(ModPreset {
+            name,
+            mods,
+            dlcs,
+            local_mods,
+            warnings: vec![],
+        })
+    }
+
+    /// Parses a plain text list with one workshop id or url per line. Empty lines and `#` or `//` comments are skipped
+    pub fn from_id_list(raw_contents: &str, filename: Option<&OsStr>) -> Result<Self, PresetError> {
+        let mut mods: Vec<Mod> = vec![];
+        let mut warnings = vec![];
+
+        for line in raw_contents.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+                continue;
+            }
+            match parse_workshop_ref(line) {
+                Ok(id) => {
+                    let item = Mod::from_workshop_id(id, None);
+                    if !mods.contains(&item) {
+                        mods.push(item);
+                    }
+                }
+                Err(reason) => warnings.push(PresetWarning {
+                    entry: line.to_string(),
+                    reason,
+                }),
+            }
+        }
+
+        if mods.is_empty() {
+            return Err(PresetError::NoModTable);
+        }
+        let mut preset = ModPreset::from_mods(name_from_filename(filename), mods);
+        preset.warnings = warnings;
+        Ok(preset)
+    }
+
+    /// Parses csv exports. The workshop id column is picked once for the whole file, by its header when
+    /// there is one, otherwise the column holding a workshop id or url in every row. The name comes from
+    /// the name column, or the first column without ids
+    pub fn from_csv(raw_contents: &str, filename: Option<&OsStr>) -> Result<Self, PresetError> {
+        let lines: Vec<&str> = raw_contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let Some(first_line) = lines.first() else {
+            return Err(PresetError::NoModTable);
+        };
+        let separator = csv_separator(first_line);
+        let mut rows: Vec<Vec<String>> = lines
+            .iter()
+            .map(|line| split_csv_line(line, separator))
+            .collect();
+
+        // a first row without a single workshop id is the header
+        let header = match rows[0].iter().all(|cell| parse_workshop_ref(cell).is_err()) {
+            true => Some(rows.remove(0)),
+            false => None,
+        };
+        let header_column = |names: &[&str]| -> Option<usize> {
+            let header = header.as_ref()?;
+            names.iter().find_map(|name| {
+                header
+                    .iter()
+                    .position(|cell| cell.to_lowercase().contains(name))
+            })
+        };
+
+        let id_column = header_column(&["workshop", "published", "link", "url"])
+            .or_else(|| {
+                let header = header.as_ref()?;
+                header
+                    .iter()
+                    .position(|cell| cell.eq_ignore_ascii_case("id"))
+            })
+            .or_else(|| csv_id_column(&rows))
+            .ok_or(PresetError::NoModTable)?;
+        let name_column = header_column(&["name", "title", "mod"])
+            .filter(|column| *column != id_column)
+            .or_else(|| {
+                // the first column that never holds an id, so counters and versions are no names
+                let columns = rows.iter().map(Vec::len).max().unwrap_or_default();
+                (0..columns).find(|column| {
+                    *column != id_column
+                        && rows.iter().all(|row| {
+                            row.get(*column)
+                                .is_none_or(|cell| parse_workshop_ref(cell).is_err())
+                        })
+                })
             });
+
+        let mut mods: Vec<Mod> = vec![];
+        let mut warnings = vec![];
+        for row in &rows {
+            // rows without an id are notes
+            let Some(cell) = row.get(id_column).filter(|cell| !cell.is_empty()) else {
+                continue;
+            };
+            let id = match parse_workshop_ref(cell) {
+                Ok(id) => id,
+                Err(reason) => {
+                    warnings.push(PresetWarning {
+                        entry: cell.to_string(),
+                        reason,
+                    });
+                    continue;
+                }
+            };
+            let name = name_column
+                .and_then(|column| row.get(column))
+                .filter(|name| !name.is_empty())
+                .cloned();
+
+            let item = Mod::from_workshop_id(id, name);
+            if !mods.contains(&item) {
+                mods.push(item);
+            }
         }
 
+        if mods.is_empty() {
+            return Err(PresetError::NoModTable);
+        }
+        let mut preset = ModPreset::from_mods(name_from_filename(filename), mods);
+        preset.warnings = warnings;
+        Ok(preset)
+    }
+
+    /// Parses a `-mod=@ace;@cba_a3` startup parameter. Folders are looked up in `mod_dirs` (unless absolute),
+    /// and their meta.cpp tells us the workshop id. Folders without one are kept as local mods
+    pub async fn from_mod_param(
+        raw_contents: &str,
+        filename: Option<&OsStr>,
+        mod_dirs: &[PathBuf],
+    ) -> Result<Self, PresetError> {
+        let mut mods: Vec<Mod> = vec![];
+        let mut local_mods = vec![];
+
+        for folder in parse_mod_param(raw_contents) {
+            let folder_path = Path::new(&folder);
+            let candidates: Vec<PathBuf> = match folder_path.is_absolute() {
+                true => vec![folder_path.to_path_buf()],
+                false => mod_dirs.iter().map(|dir| dir.join(&folder)).collect(),
+            };
+
+            let mut meta = None;
+            for candidate in candidates {
+                meta = ModMeta::read_from_dir(&candidate).await;
+                if meta.is_some() {
+                    break;
+                }
+            }
+
+            match meta {
+                Some(ModMeta {
+                    published_id: Some(id),
+                    name,
+                    ..
+                }) => {
+                    let item = Mod::from_workshop_id(id, name);
+                    if !mods.contains(&item) {
+                        mods.push(item);
+                    }
+                }
+                meta => {
+                    let folder_name = folder
+                        .split(['\\', '/'])
+                        .rfind(|part| !part.is_empty())
+                        .unwrap_or(&folder)
+                        .to_string();
+                    local_mods.push(LocalMod {
+                        name: meta.and_then(|meta| meta.name).unwrap_or(folder_name),
+                        folder,
+                    });
+                }
+            }
+        }
+
+        if mods.is_empty() && local_mods.is_empty() {
+            return Err(PresetError::NoModTable);
+        }
         Ok(ModPreset {
-            name: name.to_string(),
+            name: name_from_filename(filename),
             mods,
+            dlcs: vec![],
+            local_mods,
+            warnings: vec![],
         })
     }
 
+    /// Replaces placeholder names with the names of the same mods from `known`, like the subscribed mods
+    pub fn resolve_names(&mut self, known: &[Mod]) {
+        for item in self
+            .mods
+            .iter_mut()
+            .filter(|item| item.has_placeholder_name())
+        {
+            if let Some(found) = known.iter().find(|k| k.id == item.id) {
+                item.name = found.name.clone();
+            }
+        }
+    }
+
+    /// Preset made from a plain list of mods, like the currently subscribed ones
+    pub fn from_mods(name: String, mods: Vec<Mod>) -> Self {
+        ModPreset {
+            name,
+            mods,
+            dlcs: vec![],
+            local_mods: vec![],
+            warnings: vec![],
+        }
+    }
+
+    /// Combines several presets into one, keeping each mod, dlc and local mod only once
+    pub fn merge(name: String, presets: &[ModPreset]) -> Self {
+        let mut merged = ModPreset::from_mods(name, vec![]);
+
+        for preset in presets {
+            for item in &preset.mods {
+                if !merged.mods.contains(item) {
+                    merged.mods.push(item.clone());
+                }
+            }
+            for dlc in &preset.dlcs {
+                if !merged.dlcs.iter().any(|d| d.name == dlc.name) {
+                    merged.dlcs.push(dlc.clone());
+                }
+            }
+            for local in &preset.local_mods {
+                if !merged.local_mods.iter().any(|l| l.folder == local.folder) {
+                    merged.local_mods.push(local.clone());
+                }
+            }
+        }
+        merged
+    }
+
     pub fn get_id_list(&self) -> Vec<u64> {
-        return self.mods.iter().map(|f| f.id).collect();
+        self.mods.iter().map(|f| f.id).collect()
+    }
+
+    /// Writes the preset in the same html format the Arma 3 Launcher exports, so it can be imported there again
+    pub fn to_launcher_html(&self) -> String {
+        let name = escape_html(&self.name);
+        let mut html = String::new();
+
+        html.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<html>\n");
+        html.push_str("  <!--Created by Arma 3 Launcher: https://arma3.com-->\n");
+        html.push_str("  <head>\n");
+        html.push_str("    <meta name=\"arma:Type\" content=\"preset\" />\n");
+        html.push_str(&format!(
+            "    <meta name=\"arma:PresetName\" content=\"{}\" />\n",
+            name
+        ));
+        html.push_str(
+            "    <meta name=\"generator\" content=\"Arma 3 Launcher - https://arma3.com\" />\n",
+        );
+        html.push_str("    <title>Arma 3</title>\n");
+        html.push_str("    <style>\nbody { margin: 0; padding: 0; color: #fff; background: #000; }\nbody, th, td { font: 95%/1.3 Roboto, Segoe UI, Tahoma, Arial, Helvetica, sans-serif; }\ntd { padding: 3px 30px 3px 0; }\nh1 { padding: 20px 20px 0 20px; color: white; font-weight: 200; font-family: segoe ui; font-size: 3em; margin: 0; }\nem { font-variant: italic; color:silver; }\n.before-list { padding: 5px 20px 10px 20px; }\n.mod-list, .dlc-list { background: #222222; padding: 20px; }\n.mod-list td, .dlc-list td { color: white; }\na { color: #D18F21; text-decoration: underline; }\n.from-steam { color: #449EBD; }\n.from-local { color: gray; }\n    </style>\n");
+        html.push_str("  </head>\n  <body>\n");
+        html.push_str(&format!(
+            "    <h1>Arma 3  - Preset <strong>{}</strong></h1>\n",
+            name
+        ));
+        html.push_str("    <p class=\"before-list\">\n      <em>To import this preset, drag this file onto the Launcher window. Or click the MODS tab, then PRESET in the top right, then IMPORT at the bottom, and finally select this file.</em>\n    </p>\n");
+
+        // mods, workshop first and local ones after like the launcher does
+        html.push_str("    <div class=\"mod-list\">\n      <table>\n");
+        for item in &self.mods {
+            let url = format!(
+                "https://steamcommunity.com/sharedfiles/filedetails/?id={}",
+                item.id
+            );
+            html.push_str("        <tr data-type=\"ModContainer\">\n");
+            html.push_str(&format!(
+                "          <td data-type=\"DisplayName\">{}</td>\n",
+                escape_html(&item.name)
+            ));
+            html.push_str("          <td>\n            <span class=\"from-steam\">Steam</span>\n          </td>\n");
+            html.push_str(&format!(
+                "          <td>\n            <a href=\"{0}\" data-type=\"Link\">{0}</a>\n          </td>\n",
+                url
+            ));
+            html.push_str("        </tr>\n");
+        }
+        for local in &self.local_mods {
+            let folder = escape_html(&local.folder);
+            html.push_str("        <tr data-type=\"ModContainer\">\n");
+            html.push_str(&format!(
+                "          <td data-type=\"DisplayName\">{}</td>\n",
+                escape_html(&local.name)
+            ));
+            html.push_str("          <td>\n            <span class=\"from-local\">Local</span>\n          </td>\n");
+            html.push_str(&format!(
+                "          <td>\n            <span data-type=\"Link\" data-meta=\"local:{0}|{0}|\"></span>\n          </td>\n",
+                folder
+            ));
+            html.push_str("        </tr>\n");
+        }
+        html.push_str("      </table>\n    </div>\n");
+
+        if !self.dlcs.is_empty() {
+            html.push_str("    <div class=\"dlc-list\">\n      <table>\n");
+            for dlc in &self.dlcs {
+                html.push_str("        <tr data-type=\"DlcContainer\">\n");
+                html.push_str(&format!(
+                    "          <td data-type=\"DisplayName\">{}</td>\n",
+                    escape_html(&dlc.name)
+                ));
+                html.push_str(&format!(
+                    "          <td>\n            <a href=\"{0}\" data-type=\"Link\">{0}</a>\n          </td>\n",
+                    escape_html(&dlc.url)
+                ));
+                html.push_str("        </tr>\n");
+            }
+            html.push_str("      </table>\n    </div>\n");
+        }
+
+        html.push_str("    <div class=\"footer\">\n      <span>Created by Arma 3 Launcher by Bohemia Interactive.</span>\n    </div>\n");
+        html.push_str("  </body>\n</html>\n");
+        html
+    }
+}
+
+fn is_launcher_html(raw_contents: &str) -> bool {
+    raw_contents.contains("arma:Type") || raw_contents.contains("Arma 3 Launcher")
+}
+
+fn name_from_filename(filename: Option<&OsStr>) -> String {
+    match filename {
+        Some(file) => Path::new(file)
+            .file_stem()
+            .unwrap_or(file)
+            .to_string_lossy()
+            .to_string(),
+        None => "Unnamed Preset".to_string(),
+    }
+}
+
+/// Splits the folder list out of a `-mod=` parameter. The list can be the whole file, or part of a full command line
+fn parse_mod_param(raw_contents: &str) -> Vec<String> {
+    let list = match raw_contents.find("-mod=") {
+        Some(start) => {
+            let rest = &raw_contents[start + "-mod=".len()..];
+            // quoted parameters can contain spaces, otherwise the parameter ends at the first whitespace
+            let quoted = raw_contents[..start].ends_with('"');
+            let end = match quoted {
+                true => rest.find('"'),
+                false => rest.find(char::is_whitespace),
+            };
+            &rest[..end.unwrap_or(rest.len())]
+        }
+        None => raw_contents,
+    };
+
+    list.split([';', '\n', '\r'])
+        .map(|folder| folder.trim().trim_matches('"').trim())
+        .filter(|folder| !folder.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Spreadsheets export with either , or ; depending on locale
+fn csv_separator(line: &str) -> char {
+    let cells = |separator| split_csv_line(line, separator).len();
+    match cells(';') > cells(',') {
+        true => ';',
+        false => ',',
+    }
+}
+
+/// Splits a csv row, separators inside quotes are part of the cell and `""` is a quote
+fn split_csv_line(line: &str, separator: char) -> Vec<String> {
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                cells.push(cell.trim().to_string());
+                cell.clear();
+            }
+            c => cell.push(c),
+        }
     }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+/// Column with a workshop id in the most rows. Links win over plain numbers, and between number
+/// columns the one with the larger ids wins, as row counters and versions are small numbers
+fn csv_id_column(rows: &[Vec<String>]) -> Option<usize> {
+    let columns = rows.iter().map(Vec::len).max()?;
+    (0..columns)
+        .filter_map(|column| {
+            let cells: Vec<&str> = rows
+                .iter()
+                .filter_map(|row| row.get(column).map(String::as_str))
+                .collect();
+            let ids: Vec<u64> = cells
+                .iter()
+                .filter_map(|cell| parse_workshop_ref(cell).ok())
+                .collect();
+            let links = cells.iter().filter(|cell| cell.contains("://")).count();
+            let smallest = *ids.iter().min()?;
+            Some((column, (links, ids.len(), smallest)))
+        })
+        .max_by_key(|(_, rank)| *rank)
+        .map(|(column, _)| column)
+}
+
+fn escape_html(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Store links look like https://store.steampowered.com/app/1681170
+fn parse_store_app_id(url: &str) -> Option<u32> {
+    let (_, rest) = url.split_once("/app/")?;
+    rest.split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Where a loaded preset came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PresetSource {
+    File(PathBuf),
+    Collection(u64),
+}
+
+/// Preset loaded into the parser, only enabled ones count as presets to keep
+#[derive(Debug, Clone)]
+pub struct LoadedPreset {
+    pub preset: ModPreset,
+    pub source: PresetSource,
+    pub enabled: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct PresetParser {
-    presets: Vec<ModPreset>,
+    presets: Vec<LoadedPreset>,
 }
 
 impl Default for PresetParser {
@@ -119,18 +857,39 @@ impl Default for PresetParser {
 }
 
 impl PresetParser {
-    pub async fn load_files_async(paths: Vec<PathBuf>) -> Result<Arc<Vec<ModPreset>>, String> {
-        let mut presets = Vec::new();
-        for item in &paths {
-            // read into string
-            let contents = tokio::fs::read_to_string(item)
-                .await
-                .expect("File path doesn't exist");
-            // create ModPreset object
-            let new = ModPreset::new(contents, item.file_name()).expect("File parsing failed");
-            presets.push(new);
+    /// Loads every file on its own, `mod_dirs` are the folders `-mod=` lists are resolved against
+    pub async fn load_files_async(
+        paths: Vec<PathBuf>,
+        mod_dirs: Vec<PathBuf>,
+    ) -> Arc<Vec<PresetFileResult>> {
+        let mut results = Vec::new();
+        for item in paths {
+            let result = Self::load_file(&item, &mod_dirs).await;
+            results.push(PresetFileResult { path: item, result });
+        }
+        Arc::new(results)
+    }
+
+    async fn load_file(path: &Path, mod_dirs: &[PathBuf]) -> Result<ModPreset, PresetError> {
+        // read raw bytes, so we can tell encoding errors apart from io errors
+        let bytes = tokio::fs::read(path).await?;
+        let contents =
+            String::from_utf8(bytes).map_err(|e| PresetError::Encoding(e.utf8_error()))?;
+
+        match PresetFormat::detect(path, &contents) {
+            PresetFormat::Html => ModPreset::new(contents, path.file_name()),
+            PresetFormat::Preset2 => ModPreset::from_preset2(&contents, path.file_name()),
+            PresetFormat::IdList => ModPreset::from_id_list(&contents, path.file_name()),
+            PresetFormat::Csv => ModPreset::from_csv(&contents, path.file_name()),
+            PresetFormat::ModParam => {
+                // relative folders can also be next to the list itself
+                let mut dirs = mod_dirs.to_vec();
+                if let Some(parent) = path.parent() {
+                    dirs.push(parent.to_path_buf());
+                }
+                ModPreset::from_mod_param(&contents, path.file_name(), &dirs).await
+            }
         }
-        Ok(Arc::new(presets))
     }
 
     pub fn new() -> Self {
@@ -139,13 +898,70 @@ impl PresetParser {
         }
     }
 
-    pub fn set_modpresets(&mut self, presets: Vec<ModPreset>) -> Result<(), String> {
-        self.presets = presets;
+    /// Adds presets to the loaded list. A preset loaded from the same source again replaces the old one
+    pub fn add_modpresets(
+        &mut self,
+        presets: Vec<(PresetSource, ModPreset)>,
+        enabled: bool,
+    ) -> Result<(), String> {
+        for (source, preset) in presets {
+            match self
+                .presets
+                .iter_mut()
+                .find(|loaded| loaded.source == source)
+            {
+                Some(loaded) => {
+                    loaded.preset = preset;
+                    loaded.enabled |= enabled;
+                }
+                None => self.presets.push(LoadedPreset {
+                    preset,
+                    source,
+                    enabled,
+                }),
+            }
+        }
         Ok(())
     }
 
+    pub fn set_preset_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(loaded) = self.presets.get_mut(index) {
+            loaded.enabled = enabled;
+        }
+    }
+
+    /// Fills in names of mods that were loaded by id only
+    pub fn resolve_names(&mut self, known: &[Mod]) {
+        for loaded in self.presets.iter_mut() {
+            loaded.preset.resolve_names(known);
+        }
+    }
+
+    /// Ids of the mods in any loaded preset that still only have their id as name
+    pub fn unnamed_mod_ids(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .presets
+            .iter()
+            .flat_map(|loaded| &loaded.preset.mods)
+            .filter(|item| item.has_placeholder_name())
+            .map(|item| item.id)
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    pub fn get_loaded_presets(&self) -> &[LoadedPreset] {
+        &self.presets
+    }
+
+    /// The enabled presets, which are the ones we keep mods for
     pub fn get_modpresets(&self) -> Vec<ModPreset> {
-        self.presets.clone()
+        self.presets
+            .iter()
+            .filter(|loaded| loaded.enabled)
+            .map(|loaded| loaded.preset.clone())
+            .collect()
     }
 
     pub fn get_all_mod_ids_unique(&self) -> Result<Vec<u64>, String> {
@@ -153,7 +969,7 @@ impl PresetParser {
         let mut all_mods = Vec::new();
 
         // loop all presets
-        for set in &self.presets {
+        for set in self.get_modpresets() {
             all_mods.append(&mut set.get_id_list());
         }
 
@@ -164,3 +980,193 @@ impl PresetParser {
         Ok(all_mods)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_preset() -> ModPreset {
+        let mut preset = ModPreset::from_mods(
+            "Ops & <Training> \"Night\"".to_string(),
+            vec![
+                Mod::from_workshop_id(450814997, Some("CBA_A3".to_string())),
+                Mod::from_workshop_id(463939057, Some("ace & friends".to_string())),
+                Mod::from_workshop_id(843425103, Some("RHS <AFRF> \"core\"".to_string())),
+            ],
+        );
+        preset.local_mods = vec![LocalMod {
+            name: "Local & <Test>".to_string(),
+            folder: "@local_test".to_string(),
+        }];
+        preset.dlcs = vec![Dlc {
+            name: "Western Sahara".to_string(),
+            app_id: Some(1681170),
+            url: "https://store.steampowered.com/app/1681170".to_string(),
+        }];
+        preset
+    }
+
+    fn mod_rows(preset: &ModPreset) -> Vec<(u64, String)> {
+        preset
+            .mods
+            .iter()
+            .map(|item| (item.id, item.name.clone()))
+            .collect()
+    }
+
+    fn csv_mods(raw: &str) -> Vec<(u64, String)> {
+        mod_rows(&ModPreset::from_csv(raw, None).unwrap())
+    }
+
+    #[test]
+    fn csv_id_column_comes_from_header() {
+        let raw = "#,Name,Workshop ID\n1,CBA_A3,450814997\n2,ace,463939057\n";
+
+        assert_eq!(
+            csv_mods(raw),
+            vec![
+                (450814997, "CBA_A3".to_string()),
+                (463939057, "ace".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_without_header_prefers_links_over_numbers() {
+        let raw = "CBA,12,https://steamcommunity.com/sharedfiles/filedetails/?id=450814997\n\
+                   \"RHS: AFRF, core\",4580,843425103\n";
+
+        assert_eq!(
+            csv_mods(raw),
+            vec![
+                (450814997, "CBA".to_string()),
+                (843425103, "RHS: AFRF, core".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_without_header_skips_counter_column() {
+        let raw = "1;CBA_A3;450814997\n2;\"ace; the mod\";463939057\n";
+
+        assert_eq!(
+            csv_mods(raw),
+            vec![
+                (450814997, "CBA_A3".to_string()),
+                (463939057, "ace; the mod".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_warns_about_broken_ids_in_the_id_column() {
+        let raw =
+            "Name,Link\nCBA,https://steamcommunity.com/sharedfiles/filedetails/?id=450814997\n\
+                   Broken,https://example.com/mod\nNote,\n";
+        let preset = ModPreset::from_csv(raw, None).unwrap();
+
+        assert_eq!(preset.get_id_list(), vec![450814997]);
+        assert_eq!(preset.warnings.len(), 1);
+        assert_eq!(preset.warnings[0].entry, "https://example.com/mod");
+    }
+
+    #[test]
+    fn csv_cells_keep_quoted_separators_and_quotes() {
+        assert_eq!(
+            split_csv_line("\"a, b\", \"say \"\"hi\"\"\",c", ','),
+            vec!["a, b", "say \"hi\"", "c"]
+        );
+    }
+
+    #[test]
+    fn launcher_html_round_trips() {
+        let preset = sample_preset();
+        let parsed = ModPreset::new(preset.to_launcher_html(), None).unwrap();
+
+        assert_eq!(parsed.name, preset.name);
+        assert_eq!(mod_rows(&parsed), mod_rows(&preset));
+        assert_eq!(parsed.local_mods, preset.local_mods);
+        assert_eq!(parsed.dlcs, preset.dlcs);
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn launcher_html_round_trips_twice() {
+        let once = ModPreset::new(sample_preset().to_launcher_html(), None).unwrap();
+        let twice = ModPreset::new(once.to_launcher_html(), None).unwrap();
+
+        assert_eq!(twice.to_launcher_html(), once.to_launcher_html());
+    }
+
+    #[test]
+    fn launcher_html_without_dlcs_round_trips() {
+        let mut preset = sample_preset();
+        preset.dlcs.clear();
+        let parsed = ModPreset::new(preset.to_launcher_html(), None).unwrap();
+
+        assert!(parsed.dlcs.is_empty());
+        assert_eq!(mod_rows(&parsed), mod_rows(&preset));
+    }
+
+    #[test]
+    fn preset2_reads_steam_and_local_ids_and_dlcs() {
+        let raw = r#"<?xml version="1.0" encoding="utf-8"?>
+<addons-presets>
+  <last-update>2024-03-01T12:00:00+01:00</last-update>
+  <published-ids>
+    <id>steam:450814997</id>
+    <id>463939057</id>
+    <id>local:C:\Arma 3\@local_test\</id>
+  </published-ids>
+  <dlcs-appids>
+    <id>1681170</id>
+  </dlcs-appids>
+</addons-presets>"#;
+        let preset = ModPreset::from_preset2(raw, Some(OsStr::new("Ops.preset2"))).unwrap();
+
+        assert_eq!(preset.name, "Ops");
+        assert_eq!(
+            mod_rows(&preset),
+            vec![
+                (450814997, "450814997".to_string()),
+                (463939057, "463939057".to_string())
+            ]
+        );
+        assert!(preset.mods.iter().all(Mod::has_placeholder_name));
+        assert_eq!(
+            preset.local_mods,
+            vec![LocalMod {
+                name: "@local_test".to_string(),
+                folder: "C:\\Arma 3\\@local_test\\".to_string(),
+            }]
+        );
+        assert_eq!(preset.dlcs.len(), 1);
+        assert_eq!(preset.dlcs[0].app_id, Some(1681170));
+    }
+
+    #[test]
+    fn preset2_with_empty_mod_list_is_empty() {
+        let raw = "<addons-presets><published-ids/><dlcs-appids/></addons-presets>";
+        let preset = ModPreset::from_preset2(raw, None).unwrap();
+
+        assert!(preset.mods.is_empty());
+        assert!(preset.local_mods.is_empty());
+        assert!(preset.dlcs.is_empty());
+    }
+
+    #[test]
+    fn preset2_rejects_other_xml_and_bad_ids() {
+        let other = "<html><published-ids><id>450814997</id></published-ids></html>";
+        assert!(matches!(
+            ModPreset::from_preset2(other, None),
+            Err(PresetError::NotLauncherPreset)
+        ));
+
+        let bad_id =
+            "<addons-presets><published-ids><id>steam:ace</id></published-ids></addons-presets>";
+        assert!(matches!(
+            ModPreset::from_preset2(bad_id, None),
+            Err(PresetError::BadModUrl(id)) if id == "steam:ace"
+        ));
+    }
+}
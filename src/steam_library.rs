@@ -0,0 +1,281 @@
+#![deny(clippy::all)]
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::backend::InstallInfo;
+use crate::vdf::{Vdf, VdfError};
+
+pub const ARMA_APP_ID: u32 = 107410;
+
+#[derive(Debug, Clone)]
+pub enum LibraryError {
+    Read(PathBuf, String),
+    Parse(PathBuf, VdfError),
+}
+
+impl fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LibraryError::Read(path, e) => write!(f, "Failed reading {:?}: {}", path, e),
+            LibraryError::Parse(path, e) => write!(f, "Failed parsing {:?}: {}", path, e),
+        }
+    }
+}
+
+impl std::error::Error for LibraryError {}
+
+/// Steam library as listed in `libraryfolders.vdf`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryFolder {
+    pub path: PathBuf,
+    /// Apps installed in this library, older files do not list them
+    pub apps: Vec<u32>,
+}
+
+/// Installed workshop item as listed in `appworkshop_107410.acf`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestItem {
+    pub id: u64,
+    pub size: u64,
+    pub time_updated: u32,
+    pub manifest: String,
+}
+
+/// Reads the libraries out of a `libraryfolders.vdf`
+pub fn parse_library_folders(input: &str) -> Result<Vec<LibraryFolder>, VdfError> {
+    let vdf = Vdf::parse(input)?;
+    let Some(folders) = vdf.get("libraryfolders") else {
+        return Ok(vec![]);
+    };
+
+    // libraries are numbered, everything else is stats steam keeps
+    let libraries = folders
+        .entries()
+        .iter()
+        .filter(|(key, _)| key.parse::<u32>().is_ok())
+        .filter_map(|(_, library)| match library {
+            // older steam versions only stored the path
+            Vdf::Value(path) => Some(LibraryFolder {
+                path: PathBuf::from(path),
+                apps: vec![],
+            }),
+            Vdf::Section(_) => Some(LibraryFolder {
+                path: PathBuf::from(library.get_str("path")?),
+                apps: library.get("apps").map_or(vec![], |apps| {
+                    apps.entries()
+                        .iter()
+                        .filter_map(|(app_id, _)| app_id.parse().ok())
+                        .collect()
+                }),
+            }),
+        })
+        .collect();
+    Ok(libraries)
+}
+
+/// Reads the installed items out of an `appworkshop_<appid>.acf`
+pub fn parse_workshop_manifest(input: &str) -> Result<Vec<ManifestItem>, VdfError> {
+    let vdf = Vdf::parse(input)?;
+    let Some(installed) = vdf
+        .get("AppWorkshop")
+        .and_then(|workshop| workshop.get("WorkshopItemsInstalled"))
+    else {
+        return Ok(vec![]);
+    };
+
+    let items = installed
+        .entries()
+        .iter()
+        .filter_map(|(id, item)| {
+            Some(ManifestItem {
+                id: id.parse().ok()?,
+                size: item.get_str("size").and_then(|size| size.parse().ok())?,
+                time_updated: item
+                    .get_str("timeupdated")
+                    .and_then(|time| time.parse().ok())
+                    .unwrap_or_default(),
+                manifest: item.get_str("manifest").unwrap_or_default().to_string(),
+            })
+        })
+        .collect();
+    Ok(items)
+}
+
+pub fn read_library_folders(steam_root: &Path) -> Result<Vec<LibraryFolder>, LibraryError> {
+    let path = steam_root.join("steamapps").join("libraryfolders.vdf");
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| LibraryError::Read(path.clone(), e.to_string()))?;
+    parse_library_folders(&raw).map_err(|e| LibraryError::Parse(path, e))
+}
+
+pub fn read_workshop_manifest(library: &Path) -> Result<Vec<ManifestItem>, LibraryError> {
+    let path = library
+        .join("steamapps")
+        .join("workshop")
+        .join(format!("appworkshop_{}.acf", ARMA_APP_ID));
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| LibraryError::Read(path.clone(), e.to_string()))?;
+    parse_workshop_manifest(&raw).map_err(|e| LibraryError::Parse(path, e))
+}
+
+/// Folder the workshop items of arma are downloaded to in a library
+pub fn workshop_content_dir(library: &Path) -> PathBuf {
+    library
+        .join("steamapps")
+        .join("workshop")
+        .join("content")
+        .join(ARMA_APP_ID.to_string())
+}
+
+/// Every steam library on this machine, found through the known steam install folders and the
+/// library the game is installed in. Only folders that exist are returned
+pub fn find_libraries(game_install_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut steam_roots = vec![];
+
+    // <library>/steamapps/common/Arma 3 -> <library>
+    if let Some(library) = game_install_dir
+        .and_then(|dir| dir.parent())
+        .and_then(|dir| dir.parent())
+        .and_then(|dir| dir.parent())
+    {
+        steam_roots.push(library.to_path_buf());
+    }
+    if let Some(program_files) = std::env::var_os("ProgramFiles(x86)") {
+        steam_roots.push(PathBuf::from(program_files).join("Steam"));
+    }
+    if let Some(home) = std::env::var_os("HOME").map(PathBuf::from) {
+        steam_roots.push(home.join(".steam").join("steam"));
+        steam_roots.push(home.join(".local").join("share").join("Steam"));
+        steam_roots.push(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam"));
+        steam_roots.push(home.join("Library/Application Support/Steam"));
+    }
+
+    // only the main steam install lists the other libraries
+    let mut candidates = steam_roots.clone();
+    for root in &steam_roots {
+        if let Ok(libraries) = read_library_folders(root) {
+            candidates.extend(libraries.into_iter().map(|library| library.path));
+        }
+    }
+
+    // symlinked steam roots point to the same folder, only keep it once
+    let mut libraries: Vec<PathBuf> = vec![];
+    for library in candidates {
+        if let Ok(canonical) = library.canonicalize() {
+            if canonical.join("steamapps").is_dir() && !libraries.contains(&canonical) {
+                libraries.push(canonical);
+            }
+        }
+    }
+    libraries
+}
+
+/// Installed workshop items of all the libraries according to steams own manifests
+pub fn installed_items(libraries: &[PathBuf]) -> HashMap<u64, InstallInfo> {
+    let mut installed = HashMap::new();
+    for library in libraries {
+        let items = match read_workshop_manifest(library) {
+            Ok(items) => items,
+            // libraries without arma mods have no manifest
+            Err(LibraryError::Read(..)) => continue,
+            Err(e) => {
+                println!("{}", e);
+                continue;
+            }
+        };
+        let content_dir = workshop_content_dir(library);
+        installed.extend(items.into_iter().map(|item| {
+            (
+                item.id,
+                InstallInfo {
+                    folder: content_dir.join(item.id.to_string()),
+                    size_on_disk: item.size,
+                    timestamp: item.time_updated,
+                },
+            )
+        }));
+    }
+    installed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(libraries: &[LibraryFolder]) -> Vec<PathBuf> {
+        libraries
+            .iter()
+            .map(|library| library.path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn library_folders_keep_windows_paths() {
+        let libraries =
+            parse_library_folders(include_str!("../fixtures/libraryfolders.vdf")).unwrap();
+
+        assert_eq!(
+            paths(&libraries),
+            vec![
+                PathBuf::from("C:\\Program Files (x86)\\Steam"),
+                PathBuf::from("D:\\SteamLibrary"),
+            ]
+        );
+        assert_eq!(libraries[0].apps, vec![228980, 250820]);
+        assert_eq!(libraries[1].apps, vec![ARMA_APP_ID]);
+    }
+
+    #[test]
+    fn old_library_folders_only_list_paths() {
+        let libraries =
+            parse_library_folders(include_str!("../fixtures/libraryfolders_old.vdf")).unwrap();
+
+        assert_eq!(
+            paths(&libraries),
+            vec![PathBuf::from("D:\\Lib"), PathBuf::from("E:\\Steam Games")]
+        );
+        assert!(libraries.iter().all(|library| library.apps.is_empty()));
+    }
+
+    #[test]
+    fn broken_library_folders_fail() {
+        assert_eq!(
+            parse_library_folders("\"libraryfolders\"\n{\n\"0\"\n{\n\"path\" \"D:\\\\Lib\"\n}\n"),
+            Err(VdfError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn workshop_manifest_skips_items_without_size() {
+        let items =
+            parse_workshop_manifest(include_str!("../fixtures/appworkshop_107410.acf")).unwrap();
+
+        assert_eq!(
+            items,
+            vec![
+                ManifestItem {
+                    id: 450814997,
+                    size: 5600000,
+                    time_updated: 1717400000,
+                    manifest: "2741186094093938523".to_string(),
+                },
+                ManifestItem {
+                    id: 463939057,
+                    size: 731000000,
+                    time_updated: 1718100000,
+                    manifest: "7421305513584432921".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn broken_workshop_manifest_fails() {
+        assert_eq!(
+            parse_workshop_manifest("\"AppWorkshop\"\n{\n}\n}\n"),
+            Err(VdfError::UnexpectedToken(4, "}".to_string()))
+        );
+    }
+}
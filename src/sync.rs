@@ -0,0 +1,60 @@
+#![deny(clippy::all)]
+
+use std::collections::HashMap;
+
+use crate::diff::ModDiff;
+use crate::presets::Mod;
+
+/// Everything needed to make the subscriptions match the kept presets exactly
+#[derive(Debug, Clone, Default)]
+pub struct SyncPlan {
+    /// Subscribed mods in none of the kept presets
+    pub unsubscribe: Vec<Mod>,
+    /// Mods of the kept presets we are not subscribed to
+    pub subscribe: Vec<Mod>,
+    /// Install size of the mods to unsubscribe
+    pub freed_space: u64,
+    /// Workshop size of the mods to subscribe to
+    pub download_size: u64,
+    /// Mods to subscribe to the workshop did not report a size for
+    pub unknown_download_sizes: usize,
+}
+
+impl SyncPlan {
+    /// Builds the plan from a diff, `download_sizes` is the workshop size per id of the missing mods
+    pub fn new(diff: &ModDiff, download_sizes: &HashMap<u64, u64>) -> SyncPlan {
+        let unsubscribe = diff.remove.clone();
+        let subscribe: Vec<Mod> = diff
+            .not_subscribed
+            .iter()
+            .map(|missing| missing.item.clone())
+            .collect();
+
+        let freed_space = unsubscribe.iter().map(|item| item.local_filesize).sum();
+        let download_size = subscribe
+            .iter()
+            .filter_map(|item| download_sizes.get(&item.id))
+            .sum();
+        let unknown_download_sizes = subscribe
+            .iter()
+            .filter(|item| !download_sizes.contains_key(&item.id))
+            .count();
+
+        SyncPlan {
+            unsubscribe,
+            subscribe,
+            freed_space,
+            download_size,
+            unknown_download_sizes,
+        }
+    }
+
+    /// Number of subscription changes the plan makes
+    pub fn len(&self) -> usize {
+        self.unsubscribe.len() + self.subscribe.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,239 @@
+#![deny(clippy::all)]
+
+use std::fmt;
+
+/// Node of a valve KeyValues file, the format of steams `.vdf` and `.acf` files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Vdf {
+    Value(String),
+    Section(Vec<(String, Vdf)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VdfError {
+    /// The file ended inside a string or section
+    UnexpectedEnd,
+    /// A token that does not fit where it is, with the line it is on
+    UnexpectedToken(usize, String),
+}
+
+impl fmt::Display for VdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VdfError::UnexpectedEnd => write!(f, "File ended unexpectedly"),
+            VdfError::UnexpectedToken(line, token) => {
+                write!(f, "Unexpected '{}' on line {}", token, line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VdfError {}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Text(String),
+}
+
+impl Vdf {
+    /// Parses a whole file, the top level keys end up in the returned section
+    pub fn parse(input: &str) -> Result<Vdf, VdfError> {
+        let tokens = tokenize(input)?;
+        let mut tokens = tokens.into_iter().peekable();
+        let root = parse_section(&mut tokens)?;
+
+        match tokens.next() {
+            None => Ok(root),
+            Some((line, _)) => Err(VdfError::UnexpectedToken(line, "}".to_string())),
+        }
+    }
+
+    /// First entry with the given key, keys are not case sensitive like in steam
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        self.entries()
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Vdf::as_str)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::Value(value) => Some(value),
+            Vdf::Section(_) => None,
+        }
+    }
+
+    /// Entries of a section, a value has none
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::Value(_) => &[],
+            Vdf::Section(entries) => entries,
+        }
+    }
+}
+
+fn parse_section(
+    tokens: &mut std::iter::Peekable<impl Iterator<Item = (usize, Token)>>,
+) -> Result<Vdf, VdfError> {
+    let mut entries = vec![];
+
+    loop {
+        // end of file or end of this section, the caller checks which one is allowed
+        let key = match tokens.next_if(|(_, token)| *token != Token::Close) {
+            None => return Ok(Vdf::Section(entries)),
+            Some((_, Token::Text(key))) => key,
+            Some((line, _)) => return Err(VdfError::UnexpectedToken(line, "{".to_string())),
+        };
+
+        let value = match tokens.next() {
+            Some((_, Token::Text(value))) => Vdf::Value(value),
+            Some((_, Token::Open)) => {
+                let section = parse_section(tokens)?;
+                match tokens.next() {
+                    Some((_, Token::Close)) => section,
+                    _ => return Err(VdfError::UnexpectedEnd),
+                }
+            }
+            Some((line, Token::Close)) => {
+                return Err(VdfError::UnexpectedToken(line, "}".to_string()))
+            }
+            None => return Err(VdfError::UnexpectedEnd),
+        };
+        entries.push((key, value));
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, VdfError> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '{' => tokens.push((line, Token::Open)),
+            '}' => tokens.push((line, Token::Close)),
+            '/' if chars.peek() == Some(&'/') => {
+                // comment until the end of the line
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(escaped) => text.push(escaped),
+                            None => return Err(VdfError::UnexpectedEnd),
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            text.push(c);
+                        }
+                        None => return Err(VdfError::UnexpectedEnd),
+                    }
+                }
+                tokens.push((start, Token::Text(text)));
+            }
+            c => {
+                let mut text = c.to_string();
+                while let Some(next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '{' | '}' | '"') {
+                        break;
+                    }
+                    text.push(*next);
+                    chars.next();
+                }
+                // platform conditions like [$WIN32] follow a value, we read every platform
+                if !(text.starts_with('[') && text.ends_with(']')) {
+                    tokens.push((line, Token::Text(text)));
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_not_case_sensitive() {
+        let vdf = Vdf::parse("\"AppState\" { \"Name\" \"Arma 3\" }").unwrap();
+
+        assert_eq!(
+            vdf.get("appstate").and_then(|app| app.get_str("NAME")),
+            Some("Arma 3")
+        );
+    }
+
+    #[test]
+    fn escapes_and_conditions_are_read() {
+        let vdf = Vdf::parse(
+            r#""root"
+            {
+                "path"  "C:\\Steam\\steamapps"  [$WIN32]
+                "path"  "/home/steam"  [$LINUX]
+                "note"  "line\tone\nline \"two\""
+            }"#,
+        )
+        .unwrap();
+        let root = vdf.get("root").unwrap();
+
+        // the first entry wins, whatever platform it is for
+        assert_eq!(root.get_str("path"), Some("C:\\Steam\\steamapps"));
+        assert_eq!(root.entries().len(), 3);
+        assert_eq!(root.get_str("note"), Some("line\tone\nline \"two\""));
+    }
+
+    #[test]
+    fn file_ending_inside_a_section_fails() {
+        assert_eq!(
+            Vdf::parse("\"root\"\n{\n\"key\" \"value\"\n"),
+            Err(VdfError::UnexpectedEnd)
+        );
+        assert_eq!(Vdf::parse("\"root\""), Err(VdfError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn file_ending_inside_a_string_fails() {
+        assert_eq!(
+            Vdf::parse("\"root\" { \"key\" \"value }"),
+            Err(VdfError::UnexpectedEnd)
+        );
+        assert_eq!(
+            Vdf::parse("\"key\" \"value\\"),
+            Err(VdfError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn stray_close_fails_with_its_line() {
+        assert_eq!(
+            Vdf::parse("\"root\"\n{\n}\n}"),
+            Err(VdfError::UnexpectedToken(4, "}".to_string()))
+        );
+        assert_eq!(
+            Vdf::parse("\"key\" }"),
+            Err(VdfError::UnexpectedToken(1, "}".to_string()))
+        );
+    }
+}
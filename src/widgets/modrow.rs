@@ -1,6 +1,9 @@
 use humansize::{format_size, DECIMAL};
-use iced::widget::{button, checkbox, row, text, Space};
+use iced::widget::{button, checkbox, column, container, row, text, tooltip, Space, Text};
 use iced::{Element, Length, Task, Theme};
+use steamworks::ItemState;
+
+use crate::presets::WorkshopDetails;
 
 #[derive(Clone, Debug)]
 pub struct ModRow {
@@ -9,6 +12,64 @@ pub struct ModRow {
     pub url: String,
     pub file_size: u64,
     pub selected: bool,
+    pub presets: Vec<String>,
+    pub details: Option<WorkshopDetails>,
+    /// Persona name of the author, once steam knows it
+    pub author: Option<String>,
+    pub note: Option<String>,
+    pub warning: Option<String>,
+    /// Steam state of subscribed mods, with the download progress while downloading
+    pub state: Option<ItemState>,
+    pub download_progress: Option<(u64, u64)>,
+}
+
+/// Optional columns showing workshop details of the mod
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Author,
+    Created,
+    Updated,
+    WorkshopSize,
+    Rating,
+}
+
+impl Column {
+    pub const ALL: [Column; 5] = [
+        Column::Author,
+        Column::Created,
+        Column::Updated,
+        Column::WorkshopSize,
+        Column::Rating,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Column::Author => "Author",
+            Column::Created => "Created",
+            Column::Updated => "Updated",
+            Column::WorkshopSize => "Workshop Size",
+            Column::Rating => "Rating",
+        }
+    }
+
+    fn value(&self, details: &WorkshopDetails, author: Option<&str>) -> String {
+        match self {
+            Column::Author => match author {
+                Some(name) => format!("Author: {}", name),
+                None => format!("Author: {}", details.owner),
+            },
+            Column::Created => format!("Created: {}", format_date(details.time_created)),
+            Column::Updated => format!("Updated: {}", format_date(details.time_updated)),
+            Column::WorkshopSize => {
+                format!("Workshop Size: {}", format_size(details.file_size, DECIMAL))
+            }
+            Column::Rating => format!(
+                "Rating: {:.0}% of {}",
+                details.score * 100.0,
+                details.num_upvotes + details.num_downvotes
+            ),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -25,14 +86,45 @@ impl ModRow {
             url: url.to_string(),
             file_size,
             selected,
+            presets: vec![],
+            details: None,
+            author: None,
+            note: None,
+            warning: None,
+            state: None,
+            download_progress: None,
         }
     }
 
+    /// Short remark shown under the name, like why the mod is kept
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
+    /// Problem with the mod shown under the name, like a broken download
+    pub fn with_warning(mut self, warning: Option<String>) -> Self {
+        self.warning = warning;
+        self
+    }
+
+    /// Workshop details shown in the optional columns and when hovering the row
+    pub fn with_details(mut self, details: Option<WorkshopDetails>) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Presets that list this mod, shown when hovering the row
+    pub fn with_presets(mut self, presets: Vec<String>) -> Self {
+        self.presets = presets;
+        self
+    }
+
     pub fn update(&mut self, _message: &Message) -> Task<Message> {
         Task::none()
     }
 
-    pub fn view(&self) -> Element<'_, Message> {
+    pub fn view(&self, columns: &[Column]) -> Element<'_, Message> {
         // let checkbox_style = checkbox::Appearance {
         //     background: Background::Color(color!(0, 0, 0)),
         //     border_color: color!(0, 0, 0),
@@ -56,31 +148,141 @@ impl ModRow {
 
         let selection_checkbox = checkbox("", self.selected).on_toggle(Message::ToggleSelection);
 
-        row![
-            button(
-                row![
-                    text(&self.name).width(Length::FillPortion(8)),
-                    text(&self.url).width(Length::FillPortion(8)),
+        // optional columns are empty for mods the workshop was not asked about
+        let detail_columns = columns.iter().fold(row![], |row, column| {
+            let value = match &self.details {
+                Some(details) => column.value(details, self.author.as_deref()),
+                None => String::new(),
+            };
+            row.push(text(value).width(Length::FillPortion(5)))
+        });
+
+        let mut name = column![text(&self.name)];
+        if let Some(note) = &self.note {
+            name = name.push(text(note).size(12).style(text::secondary));
+        }
+        if let Some(warning) = &self.warning {
+            name = name.push(text(warning).size(12).style(text::danger));
+        }
+
+        let state = match self.state {
+            Some(state) => state_badge(state, self.download_progress),
+            None => text(""),
+        };
+
+        let mod_button = button(row![
+            name.width(Length::FillPortion(8)),
+            text(&self.url).width(Length::FillPortion(8)),
+            state.width(Length::FillPortion(4)),
+            text(format!(
+                "File Size: {}",
+                format_size(self.file_size, DECIMAL)
+            ))
+            .width(Length::FillPortion(8)),
+            detail_columns.width(Length::FillPortion(5 * columns.len() as u16)),
+            selection_checkbox,
+        ])
+        .padding(8)
+        .style(|theme: &Theme, status| {
+            let palette = theme.extended_palette();
+            match self.selected {
+                false => button::Style::default().with_background(palette.secondary.base.color),
+                _ => button::primary(theme, status),
+            }
+        })
+        .width(Length::Fill)
+        .on_press(Message::ToggleSelection(!self.selected));
+
+        // show which presets list the mod, and what the workshop knows about it, on hover
+        let mut hover: Vec<Text> = vec![];
+        if !self.presets.is_empty() {
+            hover.push(text(format!("In presets: {}", self.presets.join(", "))));
+        }
+        if let Some(details) = &self.details {
+            if !details.description.is_empty() {
+                hover.push(text(shorten(&details.description, 300)).size(12));
+            }
+            if details.owner != 0 {
+                hover.push(
                     text(format!(
-                        "File Size: {}",
-                        format_size(self.file_size, DECIMAL)
+                        "Author: https://steamcommunity.com/profiles/{}",
+                        details.owner
                     ))
-                    .width(Length::FillPortion(8)),
-                    selection_checkbox,
-                ]
-            )
-            .padding(8)
-            .style(|theme: &Theme, status| {
-                let palette = theme.extended_palette();
-                match self.selected {
-                    false => button::Style::default().with_background(palette.secondary.base.color),
-                    _ => button::primary(theme, status),
-                }
-            })
-            .width(Length::Fill)
-            .on_press(Message::ToggleSelection(!self.selected)),
-            Space::with_width(15)
-        ]
-        .into()
+                    .size(12),
+                );
+            }
+            if let Some(preview_url) = &details.preview_url {
+                hover.push(text(format!("Preview: {}", preview_url)).size(12));
+            }
+        }
+        let mod_button: Element<'_, Message> = match hover.is_empty() {
+            true => mod_button.into(),
+            false => {
+                let hover = hover
+                    .into_iter()
+                    .fold(column![].spacing(4).max_width(500), |col, line| {
+                        col.push(line)
+                    });
+                tooltip(
+                    mod_button,
+                    container(hover).padding(8).style(container::rounded_box),
+                    tooltip::Position::FollowCursor,
+                )
+                .into()
+            }
+        };
+
+        row![mod_button, Space::with_width(15)].into()
+    }
+}
+
+/// Short description of the steam state, the most pressing state first
+fn state_badge<'a>(state: ItemState, progress: Option<(u64, u64)>) -> Text<'a> {
+    if state.contains(ItemState::DOWNLOADING) {
+        return match progress {
+            Some((downloaded, total)) if total > 0 => {
+                text(format!("Downloading {}%", downloaded * 100 / total))
+            }
+            _ => text("Downloading"),
+        }
+        .style(text::primary);
+    }
+    if state.contains(ItemState::DOWNLOAD_PENDING) {
+        return text("Download pending").style(text::primary);
+    }
+    if state.contains(ItemState::NEEDS_UPDATE) {
+        return text("Needs update").style(text::danger);
+    }
+    match state.contains(ItemState::INSTALLED) {
+        true => text("Installed").style(text::success),
+        false => text("Not installed").style(text::secondary),
     }
 }
+
+/// Cuts text down to `max_chars`, marking that it was cut
+fn shorten(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Formats a unix timestamp as a yyyy-mm-dd date
+fn format_date(timestamp: u32) -> String {
+    // days to civil date, from Howard Hinnant's date algorithms
+    let days = timestamp as i64 / 86400 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
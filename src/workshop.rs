@@ -1,12 +1,90 @@
 #![deny(clippy::all)]
 
-use std::sync::mpsc;
-use steamworks::{Client, PublishedFileId, QueryResult};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use steamworks::{
+    AppId, CallbackHandle, Client, FileType, ItemState, PublishedFileId, SingleClient, SteamError,
+    SteamId, SteamServersConnected, SteamServersDisconnected,
+};
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 
+use crate::backend::{
+    BoxFuture, InstallInfo, SteamStatus, WorkshopBackend, WorkshopError, WorkshopItem,
+};
+use crate::presets::WorkshopDetails;
+use crate::steam_library;
+
+/// How long we wait on steam to answer a call before giving up
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How often steam callbacks are run
+pub const DEFAULT_CALLBACK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Runs the steam callbacks on its own thread, so waiting on steam never holds up the async runtime
+struct CallbackPump {
+    interval_ms: Arc<AtomicU64>,
+    last_beat: Arc<Mutex<Instant>>,
+    thread: JoinHandle<()>,
+}
+
+impl CallbackPump {
+    fn start(single: SingleClient, shutdown: CancellationToken) -> Result<Self, String> {
+        let interval_ms = Arc::new(AtomicU64::new(DEFAULT_CALLBACK_INTERVAL.as_millis() as u64));
+        let last_beat = Arc::new(Mutex::new(Instant::now()));
+
+        let thread_interval = interval_ms.clone();
+        let thread_beat = last_beat.clone();
+        let thread = std::thread::Builder::new()
+            .name("steam-callbacks".to_string())
+            .spawn(move || loop {
+                // run callbacks
+                single.run_callbacks();
+                *thread_beat.lock().unwrap() = Instant::now();
+
+                if shutdown.is_cancelled() {
+                    println!("Received Exit-signal breaking callback thread");
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(
+                    thread_interval.load(Ordering::Relaxed),
+                ));
+            })
+            .map_err(|e| format!("Failed to start steam callback thread: {}", e))?;
+
+        Ok(CallbackPump {
+            interval_ms,
+            last_beat,
+            thread,
+        })
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Relaxed))
+    }
+
+    fn status(&self) -> SteamStatus {
+        let since_beat = self.last_beat.lock().unwrap().elapsed();
+        // a few missed beats are fine, callbacks can take a moment while steam is busy
+        match !self.thread.is_finished()
+            && since_beat < self.interval() * 3 + Duration::from_secs(1)
+        {
+            true => SteamStatus::Connected,
+            false => SteamStatus::NotResponding(since_beat),
+        }
+    }
+}
+
 pub struct Workshop {
     client: steamworks::Client,
+    app_id: AppId,
+    timeout: Duration,
+    pump: CallbackPump,
+    /// Whether steam is connected to the steam servers, kept up to date by the callbacks below
+    servers_connected: Arc<AtomicBool>,
+    _server_callbacks: Mutex<Vec<CallbackHandle>>,
     pub thread_shutdown_signal: CancellationToken,
 }
 
@@ -15,7 +93,7 @@ impl Workshop {
         // try to initialize client
         let client_result = Client::init_app(app_id);
         if client_result.is_err() {
-            return Err("Failed to initialize steam client. Make sure steam is running in the background and restart AMDU".to_string());
+            return Err("Failed to initialize steam client. Make sure steam is running in the background and retry".to_string());
         }
 
         // if client is ok, we save it
@@ -23,28 +101,45 @@ impl Workshop {
 
         // make thread for callback running
         let token = CancellationToken::new();
-        let cloned_token = token.clone();
-
-        // create a thread for callbacks
-        tokio::spawn(async move {
-            loop {
-                // run callbacks
-                single.run_callbacks();
-                std::thread::sleep(std::time::Duration::from_millis(100));
+        let pump = CallbackPump::start(single, token.clone())?;
 
-                if cloned_token.is_cancelled() {
-                    println!("Received Exit-signal breaking callback thread");
-                    break;
-                }
-            }
+        // the callbacks running only proves our own thread is alive, steam tells us when it loses the servers
+        let servers_connected = Arc::new(AtomicBool::new(client.user().logged_on()));
+        let connected = servers_connected.clone();
+        let on_connected = client.register_callback(move |_: SteamServersConnected| {
+            connected.store(true, Ordering::Relaxed);
+        });
+        let disconnected = servers_connected.clone();
+        let on_disconnected = client.register_callback(move |e: SteamServersDisconnected| {
+            println!("Steam lost the connection to its servers: {}", e.reason);
+            disconnected.store(false, Ordering::Relaxed);
         });
 
         Ok(Workshop {
             client,
+            app_id,
+            timeout: DEFAULT_TIMEOUT,
+            pump,
+            servers_connected,
+            _server_callbacks: Mutex::new(vec![on_connected, on_disconnected]),
             thread_shutdown_signal: token,
         })
     }
 
+    /// Sets how often the steam callbacks are run, takes effect after the current wait
+    pub fn with_callback_interval(self, interval: Duration) -> Self {
+        self.pump
+            .interval_ms
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+        self
+    }
+
+    /// Sets how long to wait on steam to answer a call
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
     pub fn client(&self) -> &steamworks::Client {
         &self.client
     }
@@ -60,56 +155,203 @@ impl Workshop {
         self.client.ugc().item_install_info(item_id)
     }
 
-    pub async fn get_subscribed_mods_info(
-        &self,
-    ) -> Result<Vec<QueryResult>, oneshot::error::RecvError> {
-        // get list of subscribed mods
-        let list = self.get_subscribed_items();
-
-        // make signals, not using tokio as that apperently didn't work with this closure...
-        let (sender, receiver) = mpsc::channel();
-
-        match self.client.ugc().query_items(list) {
-            Ok(item_list_query) => {
-                item_list_query.fetch(move |query_result| {
-                    match query_result {
-                        Ok(res) => {
-                            // flatten to unpack all options/results and return only Some()
-                            let result: Vec<QueryResult> = res.iter().flatten().collect();
-                            // let main thread know we are done
-                            sender
-                                .send(result.clone())
-                                .expect("PANIC: Main thread is gone");
-                        }
-                        Err(e) => {
-                            println!("Error on query fetch: {:?}", e)
-                        }
-                    }
-                })
-            }
-            Err(e) => {
-                println!("Error on making subscribed items query, ERR: {:?}", e)
-            }
+    pub fn get_item_state(&self, item_id: PublishedFileId) -> ItemState {
+        self.client.ugc().item_state(item_id)
+    }
+
+    pub fn get_item_download_info(&self, item_id: PublishedFileId) -> Option<(u64, u64)> {
+        self.client.ugc().item_download_info(item_id)
+    }
+
+    /// Queues the item for download ahead of everything else, steam only says whether it accepted
+    pub fn download_item(&self, item_id: PublishedFileId) -> Result<(), WorkshopError> {
+        match self.client.ugc().download_item(item_id, true) {
+            true => Ok(()),
+            false => Err(WorkshopError::Steam(SteamError::Generic)),
         }
-        Ok(receiver.recv().unwrap())
     }
 
-    pub async fn unsub_from_mod(
-        &self,
-        item_id: PublishedFileId,
-    ) -> Result<(), steamworks::SteamError> {
-        // create signals
-        let (sender, receiver) = mpsc::channel();
+    pub fn is_dlc_installed(&self, dlc_app_id: u32) -> bool {
+        self.client.apps().is_dlc_installed(AppId(dlc_app_id))
+    }
+
+    /// Install folder of the game itself, where local @mods usually live
+    pub fn get_game_install_dir(&self) -> Option<PathBuf> {
+        let dir = self.client.apps().app_install_dir(self.app_id);
+        match dir.is_empty() {
+            true => None,
+            false => Some(PathBuf::from(dir)),
+        }
+    }
+
+    /// Metadata of the given items including their children, items the workshop does not know are left out.
+    /// Steam only returns one page per query, so anything past `QUERY_PAGE_SIZE` items is lost
+    pub async fn query_items(&self, ids: Vec<u64>) -> Result<Vec<WorkshopItem>, WorkshopError> {
+        let list = ids.into_iter().map(PublishedFileId).collect();
+        let (sender, receiver) = oneshot::channel();
+
+        let query = self
+            .client
+            .ugc()
+            .query_items(list)
+            .map_err(|e| WorkshopError::Query(e.to_string()))?;
+        query.include_children(true).fetch(move |query_result| {
+            let result = query_result.map(|res| {
+                // children are only available through the index on the results
+                (0..res.returned_results())
+                    .filter_map(|i| {
+                        let item = res.get(i)?;
+                        Some(WorkshopItem {
+                            id: item.published_file_id.0,
+                            title: item.title,
+                            url: item.url,
+                            tags: item.tags,
+                            is_collection: item.file_type == FileType::Collection,
+                            children: res
+                                .get_children(i)
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|child| child.0)
+                                .collect(),
+                            details: WorkshopDetails {
+                                owner: item.owner.raw(),
+                                time_created: item.time_created,
+                                time_updated: item.time_updated,
+                                file_size: item.file_size as u64,
+                                score: item.score,
+                                num_upvotes: item.num_upvotes,
+                                num_downvotes: item.num_downvotes,
+                                description: item.description,
+                                preview_url: res.preview_url(i),
+                            },
+                        })
+                    })
+                    .collect()
+            });
+            // nobody listening anymore means we timed out, so the result can go
+            let _ = sender.send(result);
+        });
+        Ok(self.wait_for_callback(receiver).await??)
+    }
+
+    pub async fn subscribe_to_mod(&self, item_id: PublishedFileId) -> Result<(), WorkshopError> {
+        let (sender, receiver) = oneshot::channel();
+
+        // call sub
+        self.client
+            .ugc()
+            .subscribe_item(item_id, move |sub_result| {
+                let _ = sender.send(sub_result);
+            });
+        Ok(self.wait_for_callback(receiver).await??)
+    }
+
+    pub async fn unsub_from_mod(&self, item_id: PublishedFileId) -> Result<(), WorkshopError> {
+        let (sender, receiver) = oneshot::channel();
 
         // call unsub
         self.client
             .ugc()
             .unsubscribe_item(item_id, move |unsub_result| {
-                sender
-                    .send(unsub_result)
-                    .expect("PANIC: Main thread is gone");
+                let _ = sender.send(unsub_result);
             });
-        receiver.recv().unwrap() // If we want to handle both steamerror and oneshot error, use crate AnyHow. Has result types that can easily be converted to.
+        Ok(self.wait_for_callback(receiver).await??)
+    }
+
+    /// Waits for a callback to answer through `receiver`, without blocking the runtime
+    async fn wait_for_callback<T>(
+        &self,
+        receiver: oneshot::Receiver<T>,
+    ) -> Result<T, WorkshopError> {
+        match tokio::time::timeout(self.timeout, receiver).await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(_)) => Err(WorkshopError::Disconnected),
+            Err(_) => Err(WorkshopError::Timeout(self.timeout)),
+        }
+    }
+}
+
+impl WorkshopBackend for Workshop {
+    fn subscribed_items(&self) -> Vec<u64> {
+        self.get_subscribed_items()
+            .into_iter()
+            .map(|id| id.0)
+            .collect()
+    }
+
+    fn query_items(
+        &self,
+        ids: Vec<u64>,
+    ) -> BoxFuture<'_, Result<Vec<WorkshopItem>, WorkshopError>> {
+        Box::pin(self.query_items(ids))
+    }
+
+    fn install_info(&self, id: u64) -> Option<InstallInfo> {
+        self.get_item_install_info(PublishedFileId(id))
+            .map(|info| InstallInfo {
+                folder: PathBuf::from(info.folder),
+                size_on_disk: info.size_on_disk,
+                timestamp: info.timestamp,
+            })
+    }
+
+    fn item_state(&self, id: u64) -> ItemState {
+        self.get_item_state(PublishedFileId(id))
+    }
+
+    fn download_progress(&self, id: u64) -> Option<(u64, u64)> {
+        self.get_item_download_info(PublishedFileId(id))
+    }
+
+    fn download(&self, id: u64) -> Result<(), WorkshopError> {
+        self.download_item(PublishedFileId(id))
+    }
+
+    fn subscribe(&self, id: u64) -> BoxFuture<'_, Result<(), WorkshopError>> {
+        Box::pin(self.subscribe_to_mod(PublishedFileId(id)))
+    }
+
+    fn unsubscribe(&self, id: u64) -> BoxFuture<'_, Result<(), WorkshopError>> {
+        Box::pin(self.unsub_from_mod(PublishedFileId(id)))
+    }
+
+    fn is_dlc_installed(&self, dlc_app_id: u32) -> bool {
+        self.is_dlc_installed(dlc_app_id)
+    }
+
+    fn game_install_dir(&self) -> Option<PathBuf> {
+        self.get_game_install_dir()
+    }
+
+    fn author_name(&self, owner: u64) -> Option<String> {
+        let friends = self.client.friends();
+        let owner = SteamId::from_raw(owner);
+        // true means steam is still asking its servers
+        match friends.request_user_information(owner, true) {
+            true => None,
+            false => Some(friends.get_friend(owner).name()),
+        }
+    }
+
+    fn steam_libraries(&self) -> Vec<PathBuf> {
+        steam_library::find_libraries(self.get_game_install_dir().as_deref())
+    }
+
+    fn steam_status(&self) -> SteamStatus {
+        // a steam client that quit does not always say so, so ask it as well
+        match self.pump.status() {
+            SteamStatus::Connected
+                if !self.servers_connected.load(Ordering::Relaxed)
+                    || !self.client.user().logged_on() =>
+            {
+                SteamStatus::Disconnected
+            }
+            status => status,
+        }
+    }
+
+    fn shutdown(&self) {
+        self.thread_shutdown_signal.cancel();
     }
 }
 
@@ -0,0 +1,167 @@
+#![deny(clippy::all)]
+
+use std::fmt;
+
+const WORKSHOP_HOSTS: [&str; 2] = ["steamcommunity.com", "www.steamcommunity.com"];
+const WORKSHOP_PATHS: [&str; 2] = ["/sharedfiles/filedetails", "/workshop/filedetails"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkshopRefError {
+    Empty,
+    UnknownHost(String),
+    UnknownPath(String),
+    MissingId,
+    InvalidId(String),
+}
+
+impl fmt::Display for WorkshopRefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkshopRefError::Empty => write!(f, "No workshop link"),
+            WorkshopRefError::UnknownHost(host) => write!(f, "Not a steam workshop link: {}", host),
+            WorkshopRefError::UnknownPath(path) => write!(f, "Not a workshop item page: {}", path),
+            WorkshopRefError::MissingId => write!(f, "Workshop link has no item id"),
+            WorkshopRefError::InvalidId(id) => write!(f, "Invalid workshop item id: '{}'", id),
+        }
+    }
+}
+
+impl std::error::Error for WorkshopRefError {}
+
+/// Reads the workshop item id from anything users paste or presets contain:
+/// - plain ids: `450814997`
+/// - item pages: `https://steamcommunity.com/sharedfiles/filedetails/?id=450814997&searchtext=123`
+/// - client links: `steam://url/CommunityFilePage/450814997` and `steam://openurl/<item page>`
+pub fn parse_workshop_ref(raw: &str) -> Result<u64, WorkshopRefError> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(WorkshopRefError::Empty);
+    }
+    if raw.chars().all(|c| c.is_ascii_digit()) {
+        return parse_id(raw);
+    }
+
+    let (scheme, rest) = raw
+        .split_once("://")
+        .ok_or_else(|| WorkshopRefError::InvalidId(raw.to_string()))?;
+
+    match scheme.to_lowercase().as_str() {
+        "steam" => parse_steam_link(rest),
+        "http" | "https" => parse_web_link(rest),
+        _ => Err(WorkshopRefError::UnknownHost(raw.to_string())),
+    }
+}
+
+fn parse_steam_link(rest: &str) -> Result<u64, WorkshopRefError> {
+    if let Some(url) = rest.strip_prefix("openurl/") {
+        return parse_workshop_ref(url);
+    }
+    match rest.strip_prefix("url/CommunityFilePage/") {
+        Some(id) => parse_id(id.trim_end_matches('/')),
+        None => Err(WorkshopRefError::UnknownPath(rest.to_string())),
+    }
+}
+
+fn parse_web_link(rest: &str) -> Result<u64, WorkshopRefError> {
+    // drop the fragment, then split host / path / query
+    let rest = rest.split('#').next().unwrap_or(rest);
+    let host_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (host, path_and_query) = rest.split_at(host_end);
+    let host = host.split(':').next().unwrap_or(host).to_lowercase();
+
+    if !WORKSHOP_HOSTS.contains(&host.as_str()) {
+        return Err(WorkshopRefError::UnknownHost(host));
+    }
+
+    let (path, query) = path_and_query
+        .split_once('?')
+        .unwrap_or((path_and_query, ""));
+    let path = path.trim_end_matches('/');
+    if !WORKSHOP_PATHS
+        .iter()
+        .any(|known| path.eq_ignore_ascii_case(known))
+    {
+        return Err(WorkshopRefError::UnknownPath(path.to_string()));
+    }
+
+    let id = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "id")
+        .map(|(_, value)| value)
+        .ok_or(WorkshopRefError::MissingId)?;
+    parse_id(id)
+}
+
+fn parse_id(raw: &str) -> Result<u64, WorkshopRefError> {
+    match raw.parse::<u64>() {
+        Ok(id) if id != 0 => Ok(id),
+        _ => Err(WorkshopRefError::InvalidId(raw.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_every_shape_users_paste() {
+        let accepted = [
+            "450814997",
+            "  450814997\n",
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=450814997",
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=450814997&searchtext=123",
+            "https://steamcommunity.com/sharedfiles/filedetails/?searchtext=123&id=450814997",
+            "http://www.steamcommunity.com/workshop/filedetails/?id=450814997#comments",
+            "https://steamcommunity.com:443/sharedfiles/filedetails?id=450814997",
+            "steam://url/CommunityFilePage/450814997",
+            "steam://url/CommunityFilePage/450814997/",
+            "steam://openurl/https://steamcommunity.com/sharedfiles/filedetails/?id=450814997",
+        ];
+        for raw in accepted {
+            assert_eq!(parse_workshop_ref(raw), Ok(450814997), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn rejects_what_is_not_a_workshop_item() {
+        let rejected = [
+            ("", WorkshopRefError::Empty),
+            (
+                "https://example.com/sharedfiles/filedetails/?id=450814997",
+                WorkshopRefError::UnknownHost("example.com".to_string()),
+            ),
+            (
+                "ftp://steamcommunity.com/sharedfiles/filedetails/?id=450814997",
+                WorkshopRefError::UnknownHost(
+                    "ftp://steamcommunity.com/sharedfiles/filedetails/?id=450814997".to_string(),
+                ),
+            ),
+            (
+                "https://steamcommunity.com/id/someone/?id=450814997",
+                WorkshopRefError::UnknownPath("/id/someone".to_string()),
+            ),
+            (
+                "steam://run/107410",
+                WorkshopRefError::UnknownPath("run/107410".to_string()),
+            ),
+            (
+                "https://steamcommunity.com/sharedfiles/filedetails/?searchtext=123",
+                WorkshopRefError::MissingId,
+            ),
+            ("0", WorkshopRefError::InvalidId("0".to_string())),
+            (
+                "steam://url/CommunityFilePage/0",
+                WorkshopRefError::InvalidId("0".to_string()),
+            ),
+            (
+                "https://steamcommunity.com/sharedfiles/filedetails/?id=abc",
+                WorkshopRefError::InvalidId("abc".to_string()),
+            ),
+            ("@ace", WorkshopRefError::InvalidId("@ace".to_string())),
+        ];
+        for (raw, error) in rejected {
+            assert_eq!(parse_workshop_ref(raw), Err(error), "{}", raw);
+        }
+    }
+}